@@ -0,0 +1,175 @@
+use crate::models::LearningItem;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Days covered by the per-day counts and the heatmap.
+pub const YEAR_DAYS: i64 = 365;
+
+/// Heatmap characters: no reviews, then four levels relative to the busiest day.
+const LEVELS: [char; 5] = ['.', '-', '+', '*', '#'];
+
+#[derive(Debug)]
+pub struct Activity {
+    pub today: NaiveDate,
+    /// Reviews per UTC day over the last `YEAR_DAYS` days, active days only
+    pub daily: BTreeMap<NaiveDate, usize>,
+    /// Consecutive study days ending today, or yesterday if today has none yet
+    pub current_streak: usize,
+    pub longest_streak: usize,
+    pub total_reviews: usize,
+    pub active_days: usize,
+}
+
+impl Activity {
+    pub fn reviews_on(&self, date: NaiveDate) -> usize {
+        self.daily.get(&date).copied().unwrap_or(0)
+    }
+
+    pub fn first_day(&self) -> NaiveDate {
+        self.today - Duration::days(YEAR_DAYS - 1)
+    }
+}
+
+/// Study activity from the review logs. Streaks count every logged review;
+/// the per-day counts cover the last year.
+pub fn build(items: &[LearningItem], now: DateTime<Utc>) -> Activity {
+    let today = now.date_naive();
+    let first = today - Duration::days(YEAR_DAYS - 1);
+    let mut days: BTreeSet<NaiveDate> = BTreeSet::new();
+    let mut daily: BTreeMap<NaiveDate, usize> = BTreeMap::new();
+    for review in items.iter().flat_map(|i| &i.reviews) {
+        let date = review.at.date_naive();
+        days.insert(date);
+        if date >= first && date <= today {
+            *daily.entry(date).or_default() += 1;
+        }
+    }
+
+    let mut longest = 0;
+    let mut run = 0;
+    let mut prev: Option<NaiveDate> = None;
+    for &date in &days {
+        run = match prev {
+            Some(p) if date - p == Duration::days(1) => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        prev = Some(date);
+    }
+
+    let mut day = if days.contains(&today) { today } else { today - Duration::days(1) };
+    let mut current = 0;
+    while days.contains(&day) {
+        current += 1;
+        day -= Duration::days(1);
+    }
+
+    Activity {
+        today,
+        total_reviews: daily.values().sum(),
+        active_days: daily.len(),
+        daily,
+        current_streak: current,
+        longest_streak: longest,
+    }
+}
+
+/// Calendar heatmap of the last year: one column per week (Monday first),
+/// one row per weekday, month names above the weeks they start in.
+pub fn heatmap(activity: &Activity) -> Vec<String> {
+    let first = activity.first_day();
+    let start = first - Duration::days(first.weekday().num_days_from_monday() as i64);
+    let weeks = ((activity.today - start).num_days() / 7 + 1) as usize;
+    let max = activity.daily.values().copied().max().unwrap_or(0);
+
+    let mut months = vec![' '; weeks];
+    let mut last_month = None;
+    let mut free_from = 0;
+    for week in 0..weeks {
+        let monday = start + Duration::days(week as i64 * 7);
+        let shown = monday.max(first);
+        if last_month != Some(shown.month()) && week >= free_from {
+            let name = shown.format("%b").to_string();
+            for (offset, c) in name.chars().enumerate() {
+                if let Some(slot) = months.get_mut(week + offset) {
+                    *slot = c;
+                }
+            }
+            free_from = week + name.len() + 1;
+        }
+        last_month = Some(shown.month());
+    }
+
+    let mut lines = vec![format!("    {}", months.iter().collect::<String>().trim_end())];
+    for (row, label) in ["Mon", "", "Wed", "", "Fri", "", "Sun"].iter().enumerate() {
+        let cells: String = (0..weeks)
+            .map(|week| {
+                let date = start + Duration::days(week as i64 * 7 + row as i64);
+                if date < first || date > activity.today {
+                    ' '
+                } else {
+                    level(activity.reviews_on(date), max)
+                }
+            })
+            .collect();
+        lines.push(format!("{label:<3} {}", cells.trim_end()));
+    }
+    lines.push(format!("    Less {} More", LEVELS.iter().collect::<String>()));
+    lines
+}
+
+fn level(count: usize, max: usize) -> char {
+    if count == 0 || max == 0 {
+        return LEVELS[0];
+    }
+    LEVELS[(count * 4).div_ceil(max).clamp(1, 4)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ReviewRecord;
+    use chrono::TimeZone;
+
+    fn item(review_days: &[u32]) -> LearningItem {
+        let at = |d: u32| Utc.with_ymd_and_hms(2026, 3, d, 12, 0, 0).unwrap();
+        LearningItem {
+            reviews: review_days.iter().map(|&d| ReviewRecord::test(at(d), "correct", 1.0)).collect(),
+            ..LearningItem::test("implement")
+        }
+    }
+
+    #[test]
+    fn test_streaks() {
+        let items = vec![item(&[1, 2, 3, 4, 8, 9]), item(&[9, 10])];
+        let now = Utc.with_ymd_and_hms(2026, 3, 10, 20, 0, 0).unwrap();
+        let a = build(&items, now);
+        assert_eq!(a.longest_streak, 4);
+        assert_eq!(a.current_streak, 3);
+        assert_eq!(a.reviews_on(now.date_naive()), 1);
+        assert_eq!(a.total_reviews, 8);
+        assert_eq!(a.active_days, 7);
+
+        // No review yet today: yesterday's streak still counts
+        let a = build(&items, now + Duration::days(1));
+        assert_eq!(a.current_streak, 3);
+        let a = build(&items, now + Duration::days(2));
+        assert_eq!(a.current_streak, 0);
+    }
+
+    #[test]
+    fn test_heatmap() {
+        let items = vec![item(&[9, 9, 9, 9, 10])];
+        let now = Utc.with_ymd_and_hms(2026, 3, 10, 20, 0, 0).unwrap();
+        let lines = heatmap(&build(&items, now));
+        assert_eq!(lines.len(), 9);
+        assert!(lines[0].contains("Mar"));
+        // 2026-03-09 is a Monday with the most reviews; the 10th has a quarter of them
+        assert!(lines[1].starts_with("Mon") && lines[1].ends_with('#'));
+        assert!(lines[2].ends_with('-'));
+        // Days after today are blank
+        assert!(lines[3].ends_with('.'));
+        assert_eq!(level(0, 4), '.');
+        assert_eq!(level(3, 4), '*');
+    }
+}
@@ -0,0 +1,183 @@
+use crate::models::LearningItem;
+use crate::sm2;
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+
+/// Upper bounds (days, inclusive) and labels of the interval buckets.
+const INTERVAL_BUCKETS: [(f64, &str); 7] = [
+    (1.0, "1d"),
+    (3.0, "2-3d"),
+    (7.0, "4-7d"),
+    (14.0, "8-14d"),
+    (30.0, "15-30d"),
+    (90.0, "31-90d"),
+    (f64::INFINITY, "90d+"),
+];
+
+/// Number of calibration bins (10 percentage points each).
+const CALIBRATION_BINS: usize = 10;
+
+/// Source name for items without one.
+pub const NO_SOURCE: &str = "none";
+
+/// One review that followed an earlier review of the same item: what the
+/// forgetting curve predicted and what actually happened.
+#[derive(Debug, Clone, Copy)]
+pub struct Outcome<'a> {
+    pub item: &'a LearningItem,
+    /// Interval scheduled by the previous review
+    pub interval_days: f64,
+    pub predicted: f64,
+    pub correct: bool,
+}
+
+/// Correct share of a group of reviews next to the mean prediction.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Tally {
+    pub reviews: usize,
+    pub correct: usize,
+    pub predicted_sum: f64,
+}
+
+impl Tally {
+    fn add(&mut self, outcome: &Outcome) {
+        self.reviews += 1;
+        self.correct += outcome.correct as usize;
+        self.predicted_sum += outcome.predicted;
+    }
+
+    /// Actual retention in percent.
+    pub fn retention(&self) -> Option<f64> {
+        (self.reviews > 0).then(|| round1(self.correct as f64 / self.reviews as f64 * 100.0))
+    }
+
+    /// Mean predicted recall in percent.
+    pub fn predicted(&self) -> Option<f64> {
+        (self.reviews > 0).then(|| round1(self.predicted_sum / self.reviews as f64 * 100.0))
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Retention {
+    pub overall: Tally,
+    /// Keyed by bucket label, in bucket order
+    pub by_interval: Vec<(&'static str, Tally)>,
+    pub by_type: BTreeMap<String, Tally>,
+    pub by_source: BTreeMap<String, Tally>,
+    /// Predicted-probability bins: (lower bound in percent, tally)
+    pub calibration: Vec<(u32, Tally)>,
+    /// Mean squared error of the predictions (lower is better)
+    pub brier: Option<f64>,
+}
+
+/// Reviews with a predecessor in the item's log, reviewed after `since`.
+/// The first review of an item has no prediction and is skipped.
+pub fn outcomes<'a>(items: &'a [LearningItem], since: Option<DateTime<Utc>>) -> Vec<Outcome<'a>> {
+    let mut out = Vec::new();
+    for item in items {
+        for pair in item.reviews.windows(2) {
+            let (prev, review) = (&pair[0], &pair[1]);
+            if since.is_some_and(|s| review.at < s) {
+                continue;
+            }
+            let Some(predicted) = sm2::recall_probability(prev.interval_days, Some(&prev.at), &review.at) else {
+                continue;
+            };
+            out.push(Outcome {
+                item,
+                interval_days: prev.interval_days,
+                predicted,
+                correct: review.result == "correct",
+            });
+        }
+    }
+    out
+}
+
+pub fn retention(outcomes: &[Outcome]) -> Retention {
+    let mut report = Retention {
+        by_interval: INTERVAL_BUCKETS.iter().map(|&(_, label)| (label, Tally::default())).collect(),
+        calibration: (0..CALIBRATION_BINS as u32)
+            .map(|i| (i * 100 / CALIBRATION_BINS as u32, Tally::default()))
+            .collect(),
+        ..Retention::default()
+    };
+    let mut squared_error = 0.0;
+    for outcome in outcomes {
+        report.overall.add(outcome);
+        let bucket = INTERVAL_BUCKETS
+            .iter()
+            .position(|&(max, _)| outcome.interval_days <= max)
+            .unwrap_or(INTERVAL_BUCKETS.len() - 1);
+        report.by_interval[bucket].1.add(outcome);
+        report.by_type.entry(outcome.item.item_type.clone()).or_default().add(outcome);
+        let source = outcome.item.source.clone().unwrap_or_else(|| NO_SOURCE.to_string());
+        report.by_source.entry(source).or_default().add(outcome);
+        let bin = ((outcome.predicted * CALIBRATION_BINS as f64) as usize).min(CALIBRATION_BINS - 1);
+        report.calibration[bin].1.add(outcome);
+        squared_error += (outcome.predicted - outcome.correct as u8 as f64).powi(2);
+    }
+    if !outcomes.is_empty() {
+        report.brier = Some((squared_error / outcomes.len() as f64 * 10000.0).round() / 10000.0);
+    }
+    report
+}
+
+fn round1(v: f64) -> f64 {
+    (v * 10.0).round() / 10.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ReviewRecord;
+    use chrono::{Duration, TimeZone};
+
+    fn item(item_type: &str, source: Option<&str>, log: &[(i64, &str, f64)]) -> LearningItem {
+        let start = Utc.with_ymd_and_hms(2026, 3, 1, 9, 0, 0).unwrap();
+        LearningItem {
+            item_type: item_type.to_string(),
+            source: source.map(str::to_string),
+            reviews: log
+                .iter()
+                .map(|&(day, result, interval_days)| ReviewRecord::test(start + Duration::days(day), result, interval_days))
+                .collect(),
+            ..LearningItem::test("implement")
+        }
+    }
+
+    #[test]
+    fn test_outcomes_predict_from_previous_review() {
+        // Reviewed exactly when due: predicted recall is 90%
+        let items = vec![item("word", None, &[(0, "correct", 1.0), (1, "correct", 3.0), (4, "incorrect", 1.0)])];
+        let outcomes = outcomes(&items, None);
+        assert_eq!(outcomes.len(), 2);
+        assert!((outcomes[0].predicted - 0.9).abs() < 1e-9);
+        assert_eq!(outcomes[1].interval_days, 3.0);
+        assert!(!outcomes[1].correct);
+
+        let since = Utc.with_ymd_and_hms(2026, 3, 3, 0, 0, 0).unwrap();
+        assert_eq!(super::outcomes(&items, Some(since)).len(), 1);
+    }
+
+    #[test]
+    fn test_retention_groups() {
+        let items = vec![
+            item("word", Some("toeic"), &[(0, "correct", 1.0), (1, "correct", 3.0), (4, "incorrect", 1.0)]),
+            item("idiom", None, &[(0, "correct", 1.0), (1, "correct", 3.0)]),
+        ];
+        let report = retention(&outcomes(&items, None));
+        assert_eq!(report.overall.reviews, 3);
+        assert_eq!(report.overall.retention(), Some(66.7));
+        assert_eq!(report.overall.predicted(), Some(90.0));
+        assert_eq!(report.by_interval[0].0, "1d");
+        assert_eq!(report.by_interval[0].1.retention(), Some(100.0));
+        assert_eq!(report.by_interval[1].1.retention(), Some(0.0));
+        assert_eq!(report.by_type["word"].reviews, 2);
+        assert_eq!(report.by_source[NO_SOURCE].reviews, 1);
+        assert_eq!(report.calibration[9].1.reviews, 3);
+        assert_eq!(report.calibration[0].1.retention(), None);
+        // (0.1² + 0.1² + 0.9²) / 3
+        assert_eq!(report.brier, Some(0.2767));
+    }
+}
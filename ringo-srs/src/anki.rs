@@ -0,0 +1,685 @@
+use crate::error::AppError;
+use crate::models::{LearningItem, NewItem, ReviewRecord};
+use crate::sm2;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use rusqlite::{params, Connection, OpenFlags};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::Path;
+use tempfile::NamedTempFile;
+
+/// Note type and deck IDs used in exported packages.
+const MODEL_ID: i64 = 1_700_000_000_001;
+const DECK_ID: i64 = 1_700_000_000_002;
+const FIELD_NAMES: &[&str] = &["Front", "Back", "Context", "Context JA"];
+
+/// A note read from an Anki package.
+#[derive(Debug, Clone)]
+pub struct AnkiNote {
+    pub input: NewItem,
+    pub created_at: DateTime<Utc>,
+    /// Scheduling state; None for cards Anki still considers new
+    pub schedule: Option<Schedule>,
+}
+
+/// SM-2 state converted from an Anki card and its review log.
+#[derive(Debug, Clone)]
+pub struct Schedule {
+    pub last_quizzed: Option<DateTime<Utc>>,
+    pub next_review: DateTime<Utc>,
+    pub times_quizzed: u32,
+    pub times_correct: u32,
+    pub ease_factor: f64,
+    pub interval_days: f64,
+    pub reviews: Vec<ReviewRecord>,
+}
+
+/// Read notes (with the state of their first card) from an .apkg file.
+///
+/// Fields are mapped by name (Front, Back, Context, Context JA) and otherwise
+/// by position: the first field is front, the second back. HTML is stripped.
+pub fn read_package(path: &Path, params: &sm2::Params) -> Result<Vec<AnkiNote>, AppError> {
+    let mut archive = zip::ZipArchive::new(fs::File::open(path)?).map_err(package_error)?;
+    let names: HashSet<String> = archive.file_names().map(String::from).collect();
+    let entry = if names.contains("collection.anki21") {
+        "collection.anki21"
+    } else if names.contains("collection.anki21b") {
+        return Err(AppError::InvalidInput(
+            "Anki package uses the newer collection format; re-export it with \"Support older Anki versions\" checked".to_string(),
+        ));
+    } else if names.contains("collection.anki2") {
+        "collection.anki2"
+    } else {
+        return Err(AppError::InvalidInput("Not an Anki package: no collection found".to_string()));
+    };
+
+    let mut bytes = Vec::new();
+    archive.by_name(entry).map_err(package_error)?.read_to_end(&mut bytes)?;
+    let tmp = NamedTempFile::new()?;
+    fs::write(tmp.path(), &bytes)?;
+    let conn = Connection::open_with_flags(tmp.path(), OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(package_error)?;
+    read_collection(&conn, params).map_err(package_error)
+}
+
+fn read_collection(conn: &Connection, params: &sm2::Params) -> rusqlite::Result<Vec<AnkiNote>> {
+    let (crt, models): (i64, String) =
+        conn.query_row("SELECT crt, models FROM col", [], |r| Ok((r.get(0)?, r.get(1)?)))?;
+    let field_names = model_field_names(&models);
+
+    let mut notes_stmt = conn.prepare("SELECT id, mid, tags, flds FROM notes ORDER BY id")?;
+    let mut card_stmt = conn.prepare(
+        "SELECT id, type, queue, due, ivl, factor, reps, lapses FROM cards WHERE nid = ? ORDER BY ord LIMIT 1",
+    )?;
+    // Ease 0 rows (manual reschedules, type 4, and cram rows) are not answers
+    let mut revlog_stmt = conn.prepare(
+        "SELECT id, ease, ivl, factor, time FROM revlog WHERE cid = ? AND ease > 0 AND type != 4 ORDER BY id",
+    )?;
+
+    let rows = notes_stmt.query_map([], |r| {
+        Ok((r.get::<_, i64>(0)?, r.get::<_, i64>(1)?, r.get::<_, String>(2)?, r.get::<_, String>(3)?))
+    })?;
+
+    let mut notes = Vec::new();
+    for row in rows {
+        let (nid, mid, tags, flds) = row?;
+        let values: Vec<String> = flds.split('\x1f').map(strip_html).collect();
+        let names = field_names.get(&mid).cloned().unwrap_or_default();
+        let field = |name: &str, fallback: Option<usize>| {
+            names
+                .iter()
+                .position(|n| n.to_lowercase().replace(' ', "_") == name)
+                .or(fallback)
+                .and_then(|idx| values.get(idx))
+                .filter(|v| !v.is_empty())
+                .cloned()
+        };
+
+        let input = NewItem {
+            front: field("front", Some(0)).unwrap_or_default(),
+            back: field("back", Some(1)).unwrap_or_default(),
+            item_type: None,
+            context: field("context", None),
+            context_ja: field("context_ja", None),
+            source: Some("anki".to_string()),
+            tags: tags.split_whitespace().map(String::from).collect(),
+        };
+
+        let card = card_stmt
+            .query_row([nid], |r| {
+                Ok(CardRow {
+                    id: r.get(0)?,
+                    card_type: r.get(1)?,
+                    queue: r.get(2)?,
+                    due: r.get(3)?,
+                    ivl: r.get(4)?,
+                    factor: r.get(5)?,
+                    reps: r.get(6)?,
+                    lapses: r.get(7)?,
+                })
+            })
+            .ok();
+        let schedule = match card {
+            Some(card) if card.card_type != 0 => {
+                let log = revlog_stmt
+                    .query_map([card.id], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?, r.get(4)?)))?
+                    .collect::<rusqlite::Result<Vec<_>>>()?;
+                Some(convert_schedule(crt, &card, &log, params))
+            }
+            _ => None,
+        };
+
+        notes.push(AnkiNote {
+            input,
+            created_at: from_millis(nid),
+            schedule,
+        });
+    }
+    Ok(notes)
+}
+
+/// Field names per note type ID, ordered by field position.
+fn model_field_names(models: &str) -> HashMap<i64, Vec<String>> {
+    let json: serde_json::Value = serde_json::from_str(models).unwrap_or_default();
+    let mut out = HashMap::new();
+    if let Some(map) = json.as_object() {
+        for (mid, model) in map {
+            let Ok(mid) = mid.parse::<i64>() else { continue };
+            let mut flds: Vec<(i64, String)> = model["flds"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|f| Some((f["ord"].as_i64()?, f["name"].as_str()?.to_string())))
+                .collect();
+            flds.sort();
+            out.insert(mid, flds.into_iter().map(|(_, name)| name).collect());
+        }
+    }
+    out
+}
+
+/// Anki intervals are days when positive and seconds when negative.
+fn anki_interval_days(ivl: i64) -> f64 {
+    if ivl > 0 {
+        ivl as f64
+    } else {
+        -ivl as f64 / 86400.0
+    }
+}
+
+fn anki_ease(factor: i64, params: &sm2::Params) -> f64 {
+    if factor > 0 {
+        (factor as f64 / 1000.0).max(params.min_ease)
+    } else {
+        params.initial_ease
+    }
+}
+
+/// Scheduling columns of an Anki card (type: 0 new, 1 learning, 2 review, 3 relearning)
+struct CardRow {
+    id: i64,
+    card_type: i64,
+    queue: i64,
+    due: i64,
+    ivl: i64,
+    factor: i64,
+    reps: i64,
+    lapses: i64,
+}
+
+/// Convert a card and its review log rows (id, ease, ivl, factor) to SM-2 state.
+fn convert_schedule(
+    crt: i64,
+    card: &CardRow,
+    log: &[(i64, i64, i64, i64, i64)],
+    params: &sm2::Params,
+) -> Schedule {
+    let ease_factor = anki_ease(card.factor, params);
+    // Cards in (re)learning have sub-day intervals; treat them like a lapse
+    let interval_days = if card.ivl > 0 { card.ivl as f64 } else { 1.0 };
+    // Review and day-learning (queue 3) cards are due on a day number counted
+    // from collection creation, as are suspended or buried review cards;
+    // intraday (re)learning cards carry a timestamp
+    let due_is_day = matches!(card.queue, 2 | 3) || (card.queue < 0 && card.card_type == 2);
+    let next_review = if due_is_day {
+        from_secs(crt) + Duration::days(card.due)
+    } else {
+        from_secs(card.due)
+    };
+
+    let reviews: Vec<ReviewRecord> = log
+        .iter()
+        .map(|&(id, ease, ivl, factor, time)| ReviewRecord {
+            at: from_millis(id),
+            result: if ease == 1 { "incorrect" } else { "correct" }.to_string(),
+            difficulty: match ease {
+                2 => Some("hard".to_string()),
+                3 => Some("good".to_string()),
+                4 => Some("easy".to_string()),
+                _ => None,
+            },
+            interval_days: anki_interval_days(ivl),
+            ease_factor: if factor > 0 { anki_ease(factor, params) } else { ease_factor },
+            response_ms: (time > 0).then_some(time as u64),
+        })
+        .collect();
+
+    let (times_quizzed, times_correct, last_quizzed) = if reviews.is_empty() {
+        let reps = card.reps.max(0) as u32;
+        let estimated_last = next_review - Duration::seconds((interval_days * 86400.0) as i64);
+        (reps, reps.saturating_sub(card.lapses.max(0) as u32), Some(estimated_last))
+    } else {
+        let correct = reviews.iter().filter(|r| r.result == "correct").count() as u32;
+        (reviews.len() as u32, correct, reviews.last().map(|r| r.at))
+    };
+
+    Schedule {
+        last_quizzed,
+        next_review,
+        times_quizzed,
+        times_correct,
+        ease_factor,
+        interval_days,
+        reviews,
+    }
+}
+
+/// Write items to an .apkg file that Anki can import.
+///
+/// Each item becomes a note of a four-field note type (Front, Back, Context,
+/// Context JA) in the "ringo" deck. Reviewed items become review cards with
+/// matching interval, ease and due date; the review log is carried over.
+pub fn write_package(items: &[&LearningItem], path: &Path, now: DateTime<Utc>) -> Result<(), AppError> {
+    let tmp = NamedTempFile::new()?;
+    {
+        let conn = Connection::open(tmp.path()).map_err(package_error)?;
+        write_collection(&conn, items, now).map_err(package_error)?;
+    }
+    let collection = fs::read(tmp.path())?;
+
+    let file = fs::File::create(path)?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default();
+    zip.start_file("collection.anki2", options).map_err(package_error)?;
+    zip.write_all(&collection)?;
+    zip.start_file("media", options).map_err(package_error)?;
+    zip.write_all(b"{}")?;
+    zip.finish().map_err(package_error)?;
+    Ok(())
+}
+
+fn write_collection(conn: &Connection, items: &[&LearningItem], now: DateTime<Utc>) -> rusqlite::Result<()> {
+    conn.execute_batch(SCHEMA)?;
+
+    // Day numbers of review cards count from the collection creation day
+    let crt_day = items
+        .iter()
+        .map(|i| i.created_at)
+        .min()
+        .unwrap_or(now)
+        .date_naive()
+        .and_hms_opt(0, 0, 0)
+        .unwrap()
+        .and_utc();
+    let crt = crt_day.timestamp();
+    let mod_secs = now.timestamp();
+    let mod_ms = now.timestamp_millis();
+
+    conn.execute(
+        "INSERT INTO col VALUES (1, ?, ?, ?, 11, 0, 0, 0, ?, ?, ?, ?, '{}')",
+        params![
+            crt,
+            mod_ms,
+            mod_ms,
+            collection_conf(items.len()).to_string(),
+            model_json(mod_secs).to_string(),
+            decks_json(mod_secs).to_string(),
+            DCONF,
+        ],
+    )?;
+
+    let mut note_ids = UniqueMillis::default();
+    let mut revlog_ids = UniqueMillis::default();
+    for (pos, item) in items.iter().enumerate() {
+        let nid = note_ids.next(item.created_at.timestamp_millis());
+        let fields = [
+            item.front.as_str(),
+            &item.back,
+            item.context.as_deref().unwrap_or(""),
+            item.context_ja.as_deref().unwrap_or(""),
+        ];
+        let flds = fields.iter().map(|f| escape_html(f)).collect::<Vec<_>>().join("\x1f");
+        let tags = if item.tags.is_empty() {
+            String::new()
+        } else {
+            format!(" {} ", item.tags.join(" "))
+        };
+        conn.execute(
+            "INSERT INTO notes VALUES (?, ?, ?, ?, -1, ?, ?, ?, ?, 0, '')",
+            params![
+                nid,
+                format!("ringo-{}", item.id),
+                MODEL_ID,
+                mod_secs,
+                tags,
+                flds,
+                item.front,
+                field_checksum(&item.front),
+            ],
+        )?;
+
+        let lapses = item.times_quizzed.saturating_sub(item.times_correct);
+        let ivl = item.interval_days.round().max(1.0) as i64;
+        let factor = (item.ease_factor * 1000.0).round() as i64;
+        let (card_type, due, card_ivl, card_factor) = if item.times_quizzed == 0 {
+            (0, pos as i64 + 1, 0, 0)
+        } else {
+            let due_day = (item.next_review - crt_day).num_days().max(0);
+            (2, due_day, ivl, factor)
+        };
+        conn.execute(
+            "INSERT INTO cards VALUES (?, ?, ?, 0, ?, -1, ?, ?, ?, ?, ?, ?, ?, 0, 0, 0, 0, '')",
+            params![
+                nid,
+                nid,
+                DECK_ID,
+                mod_secs,
+                card_type,
+                card_type,
+                due,
+                card_ivl,
+                card_factor,
+                item.times_quizzed,
+                lapses,
+            ],
+        )?;
+
+        let mut last_ivl = 0i64;
+        for (n, review) in item.reviews.iter().enumerate() {
+            let ease = match (review.result.as_str(), review.difficulty.as_deref()) {
+                ("correct", Some("hard")) => 2,
+                ("correct", Some("easy")) => 4,
+                ("correct", _) => 3,
+                _ => 1,
+            };
+            let ivl = review.interval_days.round().max(1.0) as i64;
+            conn.execute(
+                "INSERT INTO revlog VALUES (?, ?, -1, ?, ?, ?, ?, ?, ?)",
+                params![
+                    revlog_ids.next(review.at.timestamp_millis()),
+                    nid,
+                    ease,
+                    ivl,
+                    last_ivl,
+                    (review.ease_factor * 1000.0).round() as i64,
+                    review.response_ms.unwrap_or(0) as i64,
+                    if n == 0 { 0 } else { 1 },
+                ],
+            )?;
+            last_ivl = ivl;
+        }
+    }
+    Ok(())
+}
+
+/// Hands out strictly increasing millisecond IDs close to the requested time.
+#[derive(Default)]
+struct UniqueMillis {
+    last: i64,
+}
+
+impl UniqueMillis {
+    fn next(&mut self, wanted: i64) -> i64 {
+        self.last = wanted.max(self.last + 1);
+        self.last
+    }
+}
+
+fn collection_conf(count: usize) -> serde_json::Value {
+    serde_json::json!({
+        "nextPos": count + 1,
+        "estTimes": true,
+        "activeDecks": [DECK_ID],
+        "sortType": "noteFld",
+        "timeLim": 0,
+        "sortBackwards": false,
+        "addToCur": true,
+        "curDeck": DECK_ID,
+        "newSpread": 0,
+        "dueCounts": true,
+        "curModel": MODEL_ID.to_string(),
+        "collapseTime": 1200,
+    })
+}
+
+fn model_json(mod_secs: i64) -> serde_json::Value {
+    let flds: Vec<serde_json::Value> = FIELD_NAMES
+        .iter()
+        .enumerate()
+        .map(|(ord, name)| {
+            serde_json::json!({
+                "name": name, "ord": ord, "sticky": false, "rtl": false,
+                "font": "Arial", "size": 20, "media": [],
+            })
+        })
+        .collect();
+    serde_json::json!({
+        MODEL_ID.to_string(): {
+            "id": MODEL_ID,
+            "name": "ringo",
+            "type": 0,
+            "mod": mod_secs,
+            "usn": -1,
+            "sortf": 0,
+            "did": DECK_ID,
+            "tmpls": [{
+                "name": "Card 1",
+                "ord": 0,
+                "qfmt": "{{Front}}",
+                "afmt": "{{FrontSide}}<hr id=answer>{{Back}}<br><br>{{Context}}<br>{{Context JA}}",
+                "did": null,
+                "bqfmt": "",
+                "bafmt": "",
+            }],
+            "flds": flds,
+            "css": ".card { font-family: arial; font-size: 20px; text-align: center; }",
+            "latexPre": "\\documentclass[12pt]{article}\n\\special{papersize=3in,5in}\n\\usepackage[utf8]{inputenc}\n\\usepackage{amssymb,amsmath}\n\\pagestyle{empty}\n\\setlength{\\parindent}{0in}\n\\begin{document}\n",
+            "latexPost": "\\end{document}",
+            "req": [[0, "any", [0]]],
+            "tags": [],
+            "vers": [],
+        }
+    })
+}
+
+fn decks_json(mod_secs: i64) -> serde_json::Value {
+    let deck = |id: i64, name: &str| {
+        serde_json::json!({
+            "id": id, "name": name, "mod": mod_secs, "usn": -1,
+            "lrnToday": [0, 0], "revToday": [0, 0], "newToday": [0, 0], "timeToday": [0, 0],
+            "collapsed": false, "desc": "", "dyn": 0, "conf": 1,
+            "extendNew": 10, "extendRev": 50,
+        })
+    };
+    serde_json::json!({
+        "1": deck(1, "Default"),
+        DECK_ID.to_string(): deck(DECK_ID, "ringo"),
+    })
+}
+
+const DCONF: &str = r#"{"1":{"id":1,"name":"Default","mod":0,"usn":0,"maxTaken":60,"autoplay":true,"timer":0,"replayq":true,"dyn":false,"new":{"bury":true,"delays":[1,10],"initialFactor":2500,"ints":[1,4,7],"order":1,"perDay":20,"separate":true},"lapse":{"delays":[10],"leechAction":0,"leechFails":8,"minInt":1,"mult":0},"rev":{"bury":true,"ease4":1.3,"fuzz":0.05,"ivlFct":1,"maxIvl":36500,"minSpace":1,"perDay":200}}}"#;
+
+const SCHEMA: &str = "
+CREATE TABLE col (
+    id integer primary key, crt integer not null, mod integer not null, scm integer not null,
+    ver integer not null, dty integer not null, usn integer not null, ls integer not null,
+    conf text not null, models text not null, decks text not null, dconf text not null,
+    tags text not null
+);
+CREATE TABLE notes (
+    id integer primary key, guid text not null, mid integer not null, mod integer not null,
+    usn integer not null, tags text not null, flds text not null, sfld integer not null,
+    csum integer not null, flags integer not null, data text not null
+);
+CREATE TABLE cards (
+    id integer primary key, nid integer not null, did integer not null, ord integer not null,
+    mod integer not null, usn integer not null, type integer not null, queue integer not null,
+    due integer not null, ivl integer not null, factor integer not null, reps integer not null,
+    lapses integer not null, left integer not null, odue integer not null, odid integer not null,
+    flags integer not null, data text not null
+);
+CREATE TABLE revlog (
+    id integer primary key, cid integer not null, usn integer not null, ease integer not null,
+    ivl integer not null, lastIvl integer not null, factor integer not null, time integer not null,
+    type integer not null
+);
+CREATE TABLE graves (usn integer not null, oid integer not null, type integer not null);
+CREATE INDEX ix_notes_usn ON notes (usn);
+CREATE INDEX ix_cards_usn ON cards (usn);
+CREATE INDEX ix_revlog_usn ON revlog (usn);
+CREATE INDEX ix_cards_nid ON cards (nid);
+CREATE INDEX ix_cards_sched ON cards (did, queue, due);
+CREATE INDEX ix_revlog_cid ON revlog (cid);
+CREATE INDEX ix_notes_csum ON notes (csum);
+";
+
+/// Anki's duplicate-check checksum: first 8 hex digits of the SHA-1 of the
+/// sort field.
+fn field_checksum(text: &str) -> i64 {
+    let hex = sha1_smol::Sha1::from(text).digest().to_string();
+    i64::from_str_radix(&hex[..8], 16).unwrap_or(0)
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\n', "<br>")
+}
+
+/// Convert an Anki field to plain text: line-break tags become spaces,
+/// other tags are removed and common entities decoded.
+fn strip_html(field: &str) -> String {
+    let mut out = String::with_capacity(field.len());
+    let mut in_tag = false;
+    let mut tag = String::new();
+    for c in field.chars() {
+        match (in_tag, c) {
+            (false, '<') => {
+                in_tag = true;
+                tag.clear();
+            }
+            (true, '>') => {
+                in_tag = false;
+                let name = tag
+                    .trim_start_matches('/')
+                    .split(|c: char| c.is_whitespace() || c == '/')
+                    .next()
+                    .unwrap_or("")
+                    .to_lowercase();
+                if matches!(name.as_str(), "br" | "div" | "p") {
+                    out.push(' ');
+                }
+            }
+            (true, c) => tag.push(c),
+            (false, c) => out.push(c),
+        }
+    }
+    let decoded = out
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+    decoded.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn from_secs(secs: i64) -> DateTime<Utc> {
+    Utc.timestamp_opt(secs, 0).single().unwrap_or_default()
+}
+
+fn from_millis(ms: i64) -> DateTime<Utc> {
+    Utc.timestamp_millis_opt(ms).single().unwrap_or_default()
+}
+
+fn package_error(e: impl std::fmt::Display) -> AppError {
+    AppError::InvalidInput(format!("Anki package error: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_html() {
+        assert_eq!(strip_html("<b>break</b> the&nbsp;ice<br>now"), "break the ice now");
+        assert_eq!(strip_html("A &amp; B &lt;tag&gt;"), "A & B <tag>");
+        assert_eq!(strip_html("<p class=\"x\">one</p><p>two<br/>three"), "one two three");
+        // Only whole tag names break words
+        assert_eq!(strip_html("im<pre>ple</pre>ment<param name=\"a\">ed"), "implemented");
+    }
+
+    #[test]
+    fn test_escape_round_trip() {
+        let text = "a < b & c";
+        assert_eq!(strip_html(&escape_html(text)), text);
+    }
+
+    #[test]
+    fn test_field_checksum() {
+        // sha1("") = da39a3ee5e6b4b0d...
+        assert_eq!(field_checksum(""), 0xda39a3ee);
+    }
+
+    #[test]
+    fn test_convert_review_card() {
+        let crt = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap().timestamp();
+        let log = vec![
+            (Utc.with_ymd_and_hms(2026, 1, 2, 0, 0, 0).unwrap().timestamp_millis(), 3, 1, 2500, 4200),
+            (Utc.with_ymd_and_hms(2026, 1, 3, 0, 0, 0).unwrap().timestamp_millis(), 1, -600, 2300, 0),
+            (Utc.with_ymd_and_hms(2026, 1, 4, 0, 0, 0).unwrap().timestamp_millis(), 4, 4, 2450, 1500),
+        ];
+        let card = CardRow {
+            id: 1,
+            card_type: 2,
+            queue: 2,
+            due: 7,
+            ivl: 4,
+            factor: 2450,
+            reps: 3,
+            lapses: 1,
+        };
+        let s = convert_schedule(crt, &card, &log, &sm2::Params::default());
+        assert_eq!(s.next_review, Utc.with_ymd_and_hms(2026, 1, 8, 0, 0, 0).unwrap());
+        assert_eq!(s.interval_days, 4.0);
+        assert!((s.ease_factor - 2.45).abs() < 1e-9);
+        assert_eq!(s.times_quizzed, 3);
+        assert_eq!(s.times_correct, 2);
+        assert_eq!(s.reviews[1].result, "incorrect");
+        assert_eq!(s.reviews[2].difficulty.as_deref(), Some("easy"));
+        assert_eq!(s.reviews[0].response_ms, Some(4200));
+        assert_eq!(s.reviews[1].response_ms, None);
+        assert_eq!(s.last_quizzed, Some(Utc.with_ymd_and_hms(2026, 1, 4, 0, 0, 0).unwrap()));
+
+        // Ease is floored at the configured minimum
+        let params = sm2::Params { min_ease: 1.5, ..sm2::Params::default() };
+        let low = CardRow { factor: 1100, ..card };
+        assert_eq!(convert_schedule(crt, &low, &[], &params).ease_factor, 1.5);
+    }
+
+    #[test]
+    fn test_convert_due_by_queue() {
+        let crt = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap().timestamp();
+        let card = |card_type, queue, due| CardRow {
+            id: 1,
+            card_type,
+            queue,
+            due,
+            ivl: 0,
+            factor: 2500,
+            reps: 2,
+            lapses: 1,
+        };
+        let params = sm2::Params::default();
+        let day3 = Utc.with_ymd_and_hms(2026, 1, 4, 0, 0, 0).unwrap();
+        // Day-learning: a day number, like review cards
+        assert_eq!(convert_schedule(crt, &card(3, 3, 3), &[], &params).next_review, day3);
+        // Intraday relearning: a timestamp
+        let at = Utc.with_ymd_and_hms(2026, 1, 4, 10, 30, 0).unwrap();
+        assert_eq!(convert_schedule(crt, &card(3, 1, at.timestamp()), &[], &params).next_review, at);
+        // Suspended review card keeps its day number
+        assert_eq!(convert_schedule(crt, &card(2, -1, 3), &[], &params).next_review, day3);
+    }
+
+    #[test]
+    fn test_read_skips_rescheduling_rows() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap();
+        let mut item = LearningItem::test("implement");
+        item.created_at = now - Duration::days(10);
+        item.times_quizzed = 1;
+        item.times_correct = 1;
+        item.reviews = vec![ReviewRecord::test(now - Duration::days(5), "correct", 3.0)];
+        let conn = Connection::open_in_memory().unwrap();
+        write_collection(&conn, &[&item], now).unwrap();
+        let cid: i64 = conn.query_row("SELECT id FROM cards", [], |r| r.get(0)).unwrap();
+        // A manual reschedule and an ungraded cram row
+        conn.execute("INSERT INTO revlog VALUES (1, ?, -1, 0, 10, 3, 2500, 0, 4)", [cid]).unwrap();
+        conn.execute("INSERT INTO revlog VALUES (2, ?, -1, 0, 10, 3, 2500, 0, 3)", [cid]).unwrap();
+
+        let notes = read_collection(&conn, &sm2::Params::default()).unwrap();
+        let schedule = notes[0].schedule.as_ref().unwrap();
+        assert_eq!(schedule.times_quizzed, 1);
+        assert_eq!(schedule.reviews.len(), 1);
+    }
+
+    #[test]
+    fn test_unique_millis() {
+        let mut ids = UniqueMillis::default();
+        assert_eq!(ids.next(100), 100);
+        assert_eq!(ids.next(100), 101);
+        assert_eq!(ids.next(50), 102);
+        assert_eq!(ids.next(500), 500);
+    }
+}
@@ -0,0 +1,144 @@
+use crate::dedup::normalize_front;
+use crate::study::Grade;
+
+/// Similarity (normalized edit distance) at or above which a typed answer
+/// counts as close rather than wrong.
+pub const CLOSE_THRESHOLD: f64 = 0.8;
+
+/// Japanese punctuation and brackets ignored when comparing answers.
+const JA_PUNCTUATION: &str = "。、！？・…「」『』（）［］【】〜～";
+
+/// How a typed answer compares to the expected one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Exact,
+    Close,
+    Wrong,
+}
+
+impl Verdict {
+    pub fn name(self) -> &'static str {
+        match self {
+            Verdict::Exact => "exact",
+            Verdict::Close => "close",
+            Verdict::Wrong => "wrong",
+        }
+    }
+
+    /// SM-2 grade: exact → good, close → hard, wrong → again.
+    pub fn grade(self) -> Grade {
+        match self {
+            Verdict::Exact => Grade::Good,
+            Verdict::Close => Grade::Hard,
+            Verdict::Wrong => Grade::Again,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Checked {
+    pub verdict: Verdict,
+    /// The acceptable answer closest to the typed one
+    pub matched: String,
+    /// Similarity of the normalized answers (1.0 when exact)
+    pub similarity: f64,
+}
+
+/// Normalize an answer for comparison: NFKC (full/half-width), lowercase,
+/// katakana folded to hiragana, punctuation dropped, whitespace collapsed,
+/// and a leading "to"/article removed as for duplicate detection.
+pub fn normalize_answer(answer: &str) -> String {
+    let folded: String = normalize_front(answer)
+        .chars()
+        .map(katakana_to_hiragana)
+        .map(|c| {
+            if (c.is_ascii_punctuation() && c != '\'') || JA_PUNCTUATION.contains(c) {
+                ' '
+            } else {
+                c
+            }
+        })
+        .filter(|c| *c != '\'')
+        .collect();
+    folded.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Acceptable answers in an expected value: alternatives are separated by
+/// `/` (including full-width ／) or `、`.
+pub fn acceptable_answers(expected: &str) -> Vec<&str> {
+    expected
+        .split(['/', '／', '、'])
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+        .collect()
+}
+
+/// Compare a typed answer against every acceptable answer in `expected`.
+pub fn check(expected: &str, typed: &str) -> Checked {
+    let typed_key = normalize_answer(typed);
+    let mut best = Checked {
+        verdict: Verdict::Wrong,
+        matched: expected.trim().to_string(),
+        similarity: 0.0,
+    };
+    if typed_key.is_empty() {
+        return best;
+    }
+    for answer in acceptable_answers(expected) {
+        let similarity = strsim::normalized_levenshtein(&normalize_answer(answer), &typed_key);
+        if similarity > best.similarity {
+            best = Checked {
+                verdict: Verdict::Wrong,
+                matched: answer.to_string(),
+                similarity,
+            };
+        }
+    }
+    best.verdict = if best.similarity >= 1.0 {
+        Verdict::Exact
+    } else if best.similarity >= CLOSE_THRESHOLD {
+        Verdict::Close
+    } else {
+        Verdict::Wrong
+    };
+    best
+}
+
+fn katakana_to_hiragana(c: char) -> char {
+    match c {
+        'ァ'..='ヶ' => char::from_u32(c as u32 - 0x60).unwrap_or(c),
+        _ => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_answer() {
+        assert_eq!(normalize_answer("To Implement."), "implement");
+        assert_eq!(normalize_answer("ＡＢＣ　ｄｅｆ"), "abc def");
+        assert_eq!(normalize_answer("カタカナ"), "かたかな");
+        assert_eq!(normalize_answer("「実装する」。"), "実装する");
+        assert_eq!(normalize_answer("don't"), "dont");
+    }
+
+    #[test]
+    fn test_multiple_answers() {
+        assert_eq!(acceptable_answers("延期する／先延ばしにする、後回し"), vec!["延期する", "先延ばしにする", "後回し"]);
+        let checked = check("延期する / 先延ばしにする", "先延ばしにする");
+        assert_eq!(checked.verdict, Verdict::Exact);
+        assert_eq!(checked.matched, "先延ばしにする");
+    }
+
+    #[test]
+    fn test_verdicts() {
+        assert_eq!(check("implement", "Implement!").verdict, Verdict::Exact);
+        assert_eq!(check("procrastinate", "procrastinat").verdict, Verdict::Close);
+        assert_eq!(check("implement", "execute").verdict, Verdict::Wrong);
+        assert_eq!(check("ミーティング", "みーてぃんぐ").verdict, Verdict::Exact);
+        assert_eq!(check("implement", "").verdict, Verdict::Wrong);
+        assert_eq!(Verdict::Close.grade(), Grade::Hard);
+    }
+}
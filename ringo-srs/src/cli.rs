@@ -1,3 +1,4 @@
+use crate::planner::Mix;
 use clap::{Parser, Subcommand};
 use std::path::PathBuf;
 
@@ -11,13 +12,18 @@ use std::path::PathBuf;
     after_help = "EXAMPLES:\n  \
         ringo-srs add --front \"implement\" --back \"実装する\"\n  \
         ringo-srs list --due --limit 5\n  \
+        ringo-srs show item_001\n  \
         ringo-srs review item_001 correct good\n  \
         ringo-srs stats"
 )]
 pub struct Cli {
-    /// Path to data file
-    #[arg(long, env = "RINGO_SRS_DATA", default_value = "data/learning-items.json")]
-    pub data: PathBuf,
+    /// Path to data file, overriding RINGO_SRS_DATA [default: `data` from ringo-srs.toml, else data/learning-items.json]
+    #[arg(long, global = true)]
+    pub data: Option<PathBuf>,
+
+    /// Use the data file of a named profile, overriding RINGO_SRS_PROFILE (cannot be combined with --data)
+    #[arg(long, global = true, value_name = "NAME")]
+    pub profile: Option<String>,
 
     #[command(subcommand)]
     pub command: Command,
@@ -25,26 +31,50 @@ pub struct Cli {
 
 #[derive(Subcommand, Debug)]
 pub enum Command {
-    /// Add a new learning item
+    /// Add learning items (single item via options, or batch via stdin JSON)
     #[command(
         long_about = "Add a new learning item.\n\n\
-            Required options: --front (English), --back (Japanese)\n\
-            Optional options: --type (word|phrase|idiom), --context, --context-ja, --source\n\n\
-            Type is auto-detected if omitted: single word → word, multi-word → phrase.\n\
-            Duplicate detection is case-insensitive on the --front value.",
+            Single-item mode: --front (English) and --back (Japanese) are required.\n\
+            Optional options: --type (word|phrase|idiom), --context, --context-ja, --source\n\
+            Batch mode: pipe a JSON array of items via stdin (no --front/--back). Each element\n\
+            takes front, back, and optionally type, context, context_ja, source, tags.\n\
+            --type, --context, --context-ja and --source apply to elements that omit them.\n\
+            All items are saved at once; each gets an outcome (added, duplicate, or invalid)\n\
+            and one bad item does not fail the batch.\n\n\
+            Type is auto-detected if omitted: single word → word, multi-word → phrase.\n\n\
+            Duplicate detection compares normalized fronts: Unicode NFKC, case, repeated\n\
+            whitespace, trailing punctuation, and a leading \"to\" or article are ignored,\n\
+            so \"To break the ice.\" duplicates \"break the ice\". The error includes the\n\
+            existing item's ID in existing_id. Use --force to add anyway.\n\
+            Similar but not identical fronts are added and reported in near_duplicates.\n\n\
+            Inflected forms are matched by lemma using a built-in English inflection table\n\
+            (\"went\", \"has gone\" and \"go\" share the lemma \"go\"). Items sharing a lemma\n\
+            are reported in same_lemma. A suffix is only stripped when the remaining stem\n\
+            is at least 3 letters and is itself a known front or an irregular verb, so\n\
+            \"seed\" is not matched to \"see\". With --link, the new item is still added\n\
+            with all its fields, and it and the first such item (reported in linked_to)\n\
+            record each other's fronts in related_forms.",
         after_help = "EXAMPLES:\n  \
             ringo-srs add --front \"implement\" --back \"実装する\"\n  \
             ringo-srs add --front \"go shopping\" --back \"買い物に行く\" --type phrase\n  \
-            ringo-srs add --front \"break the ice\" --back \"場を和ませる\" --type idiom --source ringo-explain"
+            ringo-srs add --front \"break the ice\" --back \"場を和ませる\" --type idiom --source ringo-explain\n  \
+            ringo-srs add --front \"went\" --back \"行った\" --link\n\n\
+          BATCH MODE (stdin JSON array):\n  \
+            ringo-srs add <<'EOF'\n  \
+            [\n    \
+              {\"front\":\"eat\",\"back\":\"食べる\",\"context\":\"I ate a lot of sushi.\"},\n    \
+              {\"front\":\"go to the gym\",\"back\":\"ジムに行く\",\"type\":\"phrase\"}\n  \
+            ]\n  \
+            EOF"
     )]
     Add {
-        /// English word, phrase, or idiom
+        /// English word, phrase, or idiom (omit with --back for batch stdin mode)
         #[arg(long)]
-        front: String,
+        front: Option<String>,
 
         /// Japanese translation
         #[arg(long)]
-        back: String,
+        back: Option<String>,
 
         /// Item type: word, phrase, or idiom (auto-detected if omitted)
         #[arg(long = "type", value_name = "TYPE")]
@@ -61,19 +91,39 @@ pub enum Command {
         /// Source skill that added this item
         #[arg(long)]
         source: Option<String>,
+
+        /// Add even if an item with the same normalized front exists
+        #[arg(long)]
+        force: bool,
+
+        /// Link the item and an item sharing its lemma as related forms of each other
+        #[arg(long)]
+        link: bool,
     },
 
     /// List learning items with optional filters
     #[command(
         long_about = "List learning items with optional filters.\n\n\
             Without flags, returns all items. Filters can be combined.\n\
-            Items are returned with id, front, back, type, and context fields.",
+            Items are returned with id, front, back, type, context, and context_ja fields.\n\n\
+            OUTPUT FIELDS:\n\
+            --fields selects keys by their JSON name: any stored item field (id, type, front,\n\
+            back, context, context_ja, source, created_at, last_quizzed, next_review,\n\
+            times_quizzed, times_correct, ease_factor, interval_days, status, tags,\n\
+            related_forms, reviews, cloze_of), the derived accuracy (percent, 1 decimal, null\n\
+            if never quizzed), or any extra key kept on items.\n\
+            Every requested key is present on every item; missing values are null.\n\
+            --full returns every stored field plus the derived accuracy. All keys are always\n\
+            present: empty context, context_ja, source and cloze_of are null, and empty tags,\n\
+            related_forms and reviews are [].",
         after_help = "EXAMPLES:\n  \
             ringo-srs list                    # all items\n  \
             ringo-srs list --due              # items due for review now\n  \
             ringo-srs list --due --limit 5    # top 5 due items\n  \
             ringo-srs list --weak             # low accuracy items\n  \
-            ringo-srs list --status mastered  # mastered items only"
+            ringo-srs list --status mastered  # mastered items only\n  \
+            ringo-srs list --weak --fields id,front,times_quizzed,accuracy\n  \
+            ringo-srs list --due --full       # complete items incl. scheduling state"
     )]
     List {
         /// Show only items due for review (next_review <= now)
@@ -91,6 +141,55 @@ pub enum Command {
         /// Limit number of results returned
         #[arg(long, value_name = "N")]
         limit: Option<usize>,
+
+        /// Comma-separated item fields to include in the output
+        #[arg(long, value_name = "FIELDS", value_delimiter = ',', conflicts_with = "full")]
+        fields: Option<Vec<String>>,
+
+        /// Output complete items (all stored fields plus accuracy)
+        #[arg(long)]
+        full: bool,
+    },
+
+    /// Search items by text (fuzzy for English, substring for Japanese)
+    #[command(
+        long_about = "Search items by text.\n\n\
+            Matches front, back, context and context_ja. English queries are ranked by\n\
+            edit distance, so small typos and inflection differences still match.\n\
+            Queries containing Japanese use substring matching.\n\
+            Results are sorted by score (0.6-1.0, 1.0 = exact match) and include the\n\
+            field that matched best.",
+        after_help = "EXAMPLES:\n  \
+            ringo-srs search \"put off\"\n  \
+            ringo-srs search procrastinate --limit 3\n  \
+            ringo-srs search 先延ばし"
+    )]
+    Search {
+        /// Text to search for
+        #[arg(value_name = "QUERY")]
+        query: String,
+
+        /// Limit number of results returned
+        #[arg(long, value_name = "N")]
+        limit: Option<usize>,
+    },
+
+    /// Show full detail for a single item
+    #[command(
+        long_about = "Show full detail for a single item.\n\n\
+            The item is looked up by ID prefix, as in review.\n\
+            Returns the complete stored item (scheduling state, source, context, extra\n\
+            fields and review log) together with derived values:\n\
+            due, days_overdue, and recall_probability (predicted chance of\n\
+            recalling the item now; null if it has never been reviewed).",
+        after_help = "EXAMPLES:\n  \
+            ringo-srs show item_20260204_001\n  \
+            ringo-srs show item_20260204"
+    )]
+    Show {
+        /// Item ID or unique ID prefix
+        #[arg(value_name = "ID")]
+        id: String,
     },
 
     /// Record quiz results (single item via args, or batch via stdin JSON)
@@ -98,14 +197,18 @@ pub enum Command {
         long_about = "Record quiz results and update SRS scheduling.\n\n\
             Single-item mode: pass ID, result, and optional difficulty as arguments.\n\
             Batch mode: pipe a JSON array of results via stdin (no arguments).\n\n\
-            The SM-2 algorithm calculates the next review date based on the result.",
+            The SM-2 algorithm calculates the next review date based on the result.\n\
+            The optional response time (--response-ms, or response_ms in batch input) is\n\
+            stored in the review log. When srs.slow_answer_ms is set, a correct \"good\"\n\
+            answer slower than that is scheduled as \"hard\".",
         after_help = "SINGLE-ITEM MODE:\n  \
             ringo-srs review item_001 correct good\n  \
-            ringo-srs review item_002 incorrect\n\n\
+            ringo-srs review item_002 incorrect\n  \
+            ringo-srs review item_003 correct good --response-ms 8200\n\n\
           BATCH MODE (stdin JSON array):\n  \
             ringo-srs review <<'EOF'\n  \
             [\n    \
-              {\"id\":\"item_001\",\"result\":\"correct\",\"difficulty\":\"good\"},\n    \
+              {\"id\":\"item_001\",\"result\":\"correct\",\"difficulty\":\"good\",\"response_ms\":2400},\n    \
               {\"id\":\"item_002\",\"result\":\"incorrect\"}\n  \
             ]\n  \
             EOF"
@@ -122,6 +225,455 @@ pub enum Command {
         /// Difficulty: "easy", "good", or "hard" (only for correct answers)
         #[arg(value_name = "DIFFICULTY")]
         difficulty: Option<String>,
+
+        /// Time taken to answer, in milliseconds (single-item mode)
+        #[arg(long, value_name = "MS")]
+        response_ms: Option<u64>,
+    },
+
+    /// Drill due items interactively in the terminal
+    #[command(
+        long_about = "Run an interactive study session over due items.\n\n\
+            Each card shows the front; press space or enter to reveal the back and\n\
+            context, then grade it: 1/a again, 2/h hard, 3/g good, 4/e easy.\n\
+            Again records an incorrect review; the others record a correct review with\n\
+            that difficulty, using the same scheduling as `review`. Each grade is saved\n\
+            immediately, so quitting (q, Esc or Ctrl-C) keeps the reviews done so far.\n\
+            With --typed you type the answer instead; it is checked like `check` and\n\
+            graded automatically (exact: good, close: hard, wrong: again).\n\
+            With --reverse the back is shown and the front is the answer.\n\
+            When stdin or stdout is not a terminal, prompts are written to stderr and\n\
+            answers read line by line from stdin.\n\
+            A session summary is printed as JSON at the end.",
+        after_help = "EXAMPLES:\n  \
+            ringo-srs study\n  \
+            ringo-srs study --limit 20\n  \
+            ringo-srs study --typed --reverse\n  \
+            printf '\\n3\\n' | ringo-srs study --limit 1"
+    )]
+    Study {
+        /// Maximum number of cards in the session
+        #[arg(long, value_name = "N")]
+        limit: Option<usize>,
+
+        /// Type answers and grade them automatically
+        #[arg(long)]
+        typed: bool,
+
+        /// Show the back and ask for the front
+        #[arg(long)]
+        reverse: bool,
+    },
+
+    /// Check a typed answer and record the resulting grade
+    #[command(
+        long_about = "Check a typed answer against an item and record the review.\n\n\
+            The answer is compared with the back (or the front with --reverse) after\n\
+            normalizing case, punctuation, full/half-width characters and katakana vs.\n\
+            hiragana. Alternatives in the expected value separated by / or 、 are each\n\
+            accepted. The verdict maps to a grade: exact → good, close (similarity of\n\
+            at least 0.8) → hard, wrong → again (incorrect).",
+        after_help = "EXAMPLES:\n  \
+            ringo-srs check item_001 じっそうする\n  \
+            ringo-srs check item_001 implement --reverse\n  \
+            ringo-srs check item_001 実装 --dry-run"
+    )]
+    Check {
+        /// Item ID (prefix match supported)
+        #[arg(value_name = "ID")]
+        id: String,
+
+        /// The typed answer
+        #[arg(value_name = "ANSWER")]
+        answer: String,
+
+        /// Compare against the front instead of the back
+        #[arg(long)]
+        reverse: bool,
+
+        /// Report the verdict without recording a review
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Build quiz questions from due items
+    #[command(
+        long_about = "Build quiz questions from due items, ready to present.\n\n\
+            choice mode: each question shows an item's front (or back with --reverse)\n\
+            with --options choices. Distractors are other items' answers, preferring the\n\
+            same type and a similar length; identical answers are never offered twice.\n\
+            answer_index is the 0-based position of the correct option.\n\
+            Items with no possible distractor are skipped. Nothing is recorded: report\n\
+            results with `review`. Pass --seed to reproduce a quiz.",
+        after_help = "EXAMPLES:\n  \
+            ringo-srs quiz --mode choice --n 5\n  \
+            ringo-srs quiz --n 10 --options 3 --reverse --seed 42"
+    )]
+    Quiz {
+        /// Quiz format: choice
+        #[arg(long, value_name = "MODE", default_value = "choice", value_parser = ["choice"])]
+        mode: String,
+
+        /// Number of questions
+        #[arg(long, value_name = "N", default_value_t = 5)]
+        n: usize,
+
+        /// Choices per question, including the correct one
+        #[arg(long, value_name = "N", default_value_t = 4, value_parser = clap::value_parser!(u16).range(2..=10))]
+        options: u16,
+
+        /// Ask for the front given the back
+        #[arg(long)]
+        reverse: bool,
+
+        /// Random seed (defaults to the current time)
+        #[arg(long, value_name = "SEED")]
+        seed: Option<u64>,
+    },
+
+    /// Create cloze cards from context sentences
+    #[command(
+        long_about = "Create cloze (fill-in-the-blank) cards from items' context sentences.\n\n\
+            The item's front is blanked where it appears in the context, matching whole\n\
+            words case-insensitively or an inflected form (\"implemented\" for \"implement\").\n\
+            Explicit markers take precedence: {{c1::text}} or {{c1::text::hint}} make one\n\
+            card per number, with the hint shown in the blank.\n\n\
+            Each cloze card has type \"cloze\", the blanked sentence as front and the hidden\n\
+            text as back, and is scheduled on its own. context_ja is kept as a hint and\n\
+            shown alongside the sentence when studying. cloze_of links the card to its item.\n\
+            Cards that already exist are skipped, so the command can be re-run after adding\n\
+            items. Nothing is saved with --dry-run.",
+        after_help = "EXAMPLES:\n  \
+            ringo-srs cloze\n  \
+            ringo-srs cloze item_001\n  \
+            ringo-srs cloze --dry-run"
+    )]
+    Cloze {
+        /// Only this item (prefix match supported); all items with context otherwise
+        #[arg(value_name = "ID")]
+        id: Option<String>,
+
+        /// Report the cards without creating them
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Run a quiz across several calls, recording answers as they come
+    #[command(
+        long_about = "Run a quiz whose answers arrive over several calls, e.g. one per chat turn.\n\n\
+            start freezes up to --n due items (or a --mix plan) into a session file next to the data file\n\
+            (<name>.session.json, or session.json in a data directory) and returns the\n\
+            numbered questions with their answers. Only one session can be active.\n\
+            When the data file is encrypted, the session file is encrypted with the same passphrase.\n\
+            answer records a grade (again, hard, good, easy or 1-4) for question N as a\n\
+            review right away, so an interrupted session loses nothing. A question can\n\
+            only be answered once.\n\
+            resume returns the questions still unanswered. finish ends the session,\n\
+            deletes the session file and summarizes the results; unanswered questions\n\
+            are left unreviewed.\n\
+            resume and finish also record any answer saved in the session file but not\n\
+            yet in the data file; answers already recorded are never counted twice.",
+        after_help = "EXAMPLES:\n  \
+            ringo-srs session start --n 10\n  \
+            ringo-srs session start --n 10 --mix 1:3:1\n  \
+            ringo-srs session answer 1 good\n  \
+            ringo-srs session answer 2 again\n  \
+            ringo-srs session resume\n  \
+            ringo-srs session finish"
+    )]
+    Session {
+        #[command(subcommand)]
+        action: SessionAction,
+    },
+
+    /// Plan a balanced study session of new, due and weak items
+    #[command(
+        long_about = "Plan a study session mixing new, due and weak items.\n\n\
+            Items come from three pools: new (never reviewed, oldest first), review (due\n\
+            now, most overdue first) and weak (low accuracy, due ones first). --mix sets\n\
+            their relative shares as NEW:REVIEW:WEAK; when a pool runs short its slots go\n\
+            to the others. The pools are spread through the session, consecutive items\n\
+            alternate types where possible, and siblings (same lemma, same context\n\
+            sentence, or cloze cards of the same item) are never back-to-back unless\n\
+            nothing else is left. Nothing is recorded; `session start --mix` freezes a\n\
+            planned set as a session.",
+        after_help = "EXAMPLES:\n  \
+            ringo-srs plan\n  \
+            ringo-srs plan --size 20 --mix 2:6:2\n  \
+            ringo-srs plan --mix 0:1:1"
+    )]
+    Plan {
+        /// Number of items
+        #[arg(long, value_name = "N", default_value_t = 10)]
+        size: usize,
+
+        /// Shares of new, review and weak items
+        #[arg(long, value_name = "NEW:REVIEW:WEAK", default_value = "1:3:1")]
+        mix: Mix,
+    },
+
+    /// Import items from a CSV, TSV, or Anki package file
+    #[command(
+        long_about = "Import items from a CSV, TSV, or Anki package (.apkg) file.\n\n\
+            CSV and TSV are read from stdin if FILE is omitted.\n\
+            Columns map to item fields: front, back, type, context, context_ja, source, tags.\n\
+            By default the header row names the fields (case-insensitive; other columns are\n\
+            ignored). --columns maps columns by position instead; use - to skip a column.\n\
+            Tags may be separated by spaces, commas or semicolons; a backslash escapes a\n\
+            separator or backslash inside a tag (\"work\\,dev\" is one tag).\n\n\
+            Rows whose front matches an existing item (same normalization as add) are handled\n\
+            by --on-duplicate: skip (default) leaves the item alone, update overwrites its\n\
+            non-empty fields while keeping scheduling state, fail aborts the whole import.\n\
+            Rows without front or back are reported as invalid. Nothing is saved with --dry-run.\n\n\
+            APKG: each note becomes an item. Fields named Front, Back, Context and Context JA\n\
+            are used if present; otherwise the first field is front and the second back.\n\
+            HTML is stripped and tags are kept. The note's first card is converted to SM-2\n\
+            state: interval, ease (factor / 1000), due date, and the review log (again →\n\
+            incorrect; hard/good/easy → correct). Cards Anki considers new stay new.\n\
+            Packages exported only in the newest Anki format must be re-exported with\n\
+            \"Support older Anki versions\" checked. --on-duplicate update never\n\
+            changes scheduling state.",
+        after_help = "EXAMPLES:\n  \
+            ringo-srs import vocab.csv\n  \
+            ringo-srs import vocab.tsv --format tsv --on-duplicate update\n  \
+            ringo-srs import words.csv --no-header --columns front,back,-,context --dry-run\n  \
+            ringo-srs import deck.apkg --format apkg"
+    )]
+    Import {
+        /// Input file (reads stdin if omitted)
+        #[arg(value_name = "FILE")]
+        file: Option<PathBuf>,
+
+        /// Input format: csv, tsv, or apkg
+        #[arg(long, value_name = "FORMAT", default_value = "csv", value_parser = ["csv", "tsv", "apkg"])]
+        format: String,
+
+        /// Comma-separated field for each column (- to skip)
+        #[arg(long, value_name = "FIELDS", value_delimiter = ',')]
+        columns: Option<Vec<String>>,
+
+        /// Treat the first row as data, not a header (requires --columns)
+        #[arg(long, requires = "columns")]
+        no_header: bool,
+
+        /// What to do with rows matching an existing item: skip, update, or fail
+        #[arg(long, value_name = "MODE", default_value = "skip", value_parser = ["skip", "update", "fail"])]
+        on_duplicate: String,
+
+        /// Report what would happen without saving
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Export items to CSV, TSV, Anki package, or printable flashcards
+    #[command(
+        long_about = "Export items as CSV, TSV, an Anki package (.apkg), or printable flashcards.\n\n\
+            All items are exported unless filtered with --due-within, --status or --tag\n\
+            (filters can be combined).\n\n\
+            CSV/TSV columns: id, front, back, type, context, context_ja, source, tags\n\
+            (tags space-separated, separators inside a tag escaped with a backslash), with a\n\
+            header row. The output can be re-imported.\n\
+            With --output the file is written and a summary returned; otherwise the\n\
+            exported text is returned in the content field.\n\n\
+            APKG (requires --output): one note per item in a \"ringo\" deck with fields\n\
+            Front, Back, Context, Context JA. Reviewed items become review cards with\n\
+            their interval, ease and due date, and the review log is included.\n\n\
+            PRINT: an HTML page (print or save as PDF from a browser) of A4 sheets with\n\
+            10 cards each. Pages alternate fronts and backs (back + context sentences);\n\
+            backs are mirrored so cards line up when printed double-sided, flipping on\n\
+            the long edge. Cut along the dashed lines.",
+        after_help = "EXAMPLES:\n  \
+            ringo-srs export --output vocab.csv\n  \
+            ringo-srs export --format tsv --output vocab.tsv\n  \
+            ringo-srs export --format apkg --output ringo.apkg\n  \
+            ringo-srs export --format print --due-within 7 --output data/cards.html"
+    )]
+    Export {
+        /// Output format: csv, tsv, apkg, or print
+        #[arg(long, value_name = "FORMAT", default_value = "csv", value_parser = ["csv", "tsv", "apkg", "print"])]
+        format: String,
+
+        /// Output file (content is returned in JSON if omitted)
+        #[arg(long, value_name = "FILE")]
+        output: Option<PathBuf>,
+
+        /// Only items due within this many days (0 = due now)
+        #[arg(long, value_name = "DAYS")]
+        due_within: Option<f64>,
+
+        /// Filter by status: new, learning, reviewing, mastered
+        #[arg(long, value_name = "STATUS")]
+        status: Option<String>,
+
+        /// Only items with this tag
+        #[arg(long, value_name = "TAG")]
+        tag: Option<String>,
+    },
+
+    /// Merge another data file into this one
+    #[command(
+        long_about = "Merge another learning-items.json into the current data file.\n\n\
+            Items match by ID and normalized front, or by normalized front alone.\n\
+            Unmatched items are added (with a new ID if theirs is already used).\n\
+            For matched items, scheduling state is taken from whichever side was reviewed\n\
+            most recently, review logs are combined (shared history counted once), and\n\
+            tags, related forms and extra fields from both sides are kept.\n\
+            Differing back/context/context_ja values are reported as conflicts and resolved\n\
+            by --prefer (ours by default); a value missing on one side is filled in.\n\
+            The other file is never modified.",
+        after_help = "EXAMPLES:\n  \
+            ringo-srs merge ~/laptop/learning-items.json --dry-run\n  \
+            ringo-srs merge teammate.json --prefer theirs"
+    )]
+    Merge {
+        /// Data file to merge in
+        #[arg(value_name = "OTHER")]
+        other: PathBuf,
+
+        /// Which side wins content conflicts: ours or theirs
+        #[arg(long, value_name = "SIDE", default_value = "ours", value_parser = ["ours", "theirs"])]
+        prefer: String,
+
+        /// Report what would happen without saving
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Copy the data into a new file or per-item directory layout
+    #[command(
+        long_about = "Copy the current data into DEST using the chosen storage layout.\n\n\
+            The dir layout stores DEST/meta.json plus one DEST/items/<id>.json file per\n\
+            item, with keys in sorted order and no global last_updated timestamp. Saves\n\
+            only rewrite the files of items that changed, so the deck diffs and merges\n\
+            cleanly in git. The file layout is the usual single learning-items.json;\n\
+            encrypted is the same file sealed with a passphrase (see encrypt --help).\n\
+            Every command works with either layout: an existing directory passed as\n\
+            --data is read and written in the dir layout.\n\
+            DEST must not exist; the current data is left in place.",
+        after_help = "EXAMPLES:\n  \
+            ringo-srs convert data/deck\n  \
+            RINGO_SRS_DATA=data/deck ringo-srs stats\n  \
+            ringo-srs --data data/deck convert data/learning-items.json --layout file"
+    )]
+    Convert {
+        /// Destination file or directory (must not exist)
+        #[arg(value_name = "DEST")]
+        dest: PathBuf,
+
+        /// Storage layout to write: dir, file, or encrypted
+        #[arg(long, value_name = "LAYOUT", default_value = "dir", value_parser = ["dir", "file", "encrypted"])]
+        layout: String,
+    },
+
+    /// Encrypt the data file in place
+    #[command(
+        long_about = "Encrypt the data file in place with a passphrase.\n\n\
+            The passphrase is read from RINGO_SRS_PASSPHRASE, or from the file named by\n\
+            RINGO_SRS_KEYFILE. The key is derived with Argon2id and the data sealed with\n\
+            ChaCha20-Poly1305, so a wrong passphrase or a modified file is detected.\n\
+            Once encrypted, every command reads and writes the file encrypted as long as\n\
+            the passphrase is available. The plaintext backup (.json.bak) is removed, and\n\
+            an active session file is encrypted too.\n\
+            Only the single-file layout can be encrypted.",
+        after_help = "EXAMPLES:\n  \
+            RINGO_SRS_PASSPHRASE='correct horse' ringo-srs encrypt\n  \
+            RINGO_SRS_KEYFILE=~/.ringo.key ringo-srs list --due"
+    )]
+    Encrypt,
+
+    /// Decrypt the data file in place
+    #[command(
+        long_about = "Decrypt an encrypted data file back to plain JSON.\n\n\
+            Needs the same passphrase (RINGO_SRS_PASSPHRASE or RINGO_SRS_KEYFILE)\n\
+            used to encrypt it. An active session file is decrypted too.",
+        after_help = "EXAMPLE:\n  \
+            RINGO_SRS_KEYFILE=~/.ringo.key ringo-srs decrypt"
+    )]
+    Decrypt,
+
+    /// Manage named profiles (separate data files per person)
+    #[command(
+        long_about = "Manage named profiles stored in ringo-srs.toml.\n\n\
+            Each profile maps a name to its own data file, so several people can share\n\
+            one installation or repository clone. Select one with --profile NAME or\n\
+            RINGO_SRS_PROFILE. --profile and --data cannot be combined; a profile from\n\
+            RINGO_SRS_PROFILE yields to --data, and RINGO_SRS_DATA yields to a profile.\n\
+            Profiles are read from the user-level config and the project config; a project\n\
+            profile replaces a user-level one of the same name (origin in `profiles list`).\n\
+            create and delete edit the project file: ./ringo-srs.toml, or RINGO_SRS_CONFIG.\n\
+            create stores --data as the profile's data file (default data/profiles/NAME.json).\n\
+            Relative data paths are resolved against the configuration file's directory.",
+        after_help = "EXAMPLES:\n  \
+            ringo-srs profiles create alice\n  \
+            ringo-srs profiles create bob --data data/bob/items\n  \
+            ringo-srs --profile alice add --front \"implement\" --back \"実装する\"\n  \
+            ringo-srs profiles list\n  \
+            ringo-srs profiles delete bob --purge"
+    )]
+    Profiles {
+        #[command(subcommand)]
+        action: ProfilesAction,
+    },
+
+    /// Show or change configuration (data path, scheduler parameters)
+    #[command(
+        long_about = "Show or change the TOML configuration.\n\n\
+            Settings are read from the user-level file (~/.config/ringo-srs/config.toml,\n\
+            or RINGO_SRS_USER_CONFIG) and then the project file (./ringo-srs.toml, or\n\
+            RINGO_SRS_CONFIG); the project file wins. For the data path, RINGO_SRS_DATA,\n\
+            then RINGO_SRS_PROFILE, then --data or --profile (not both) take precedence\n\
+            over both files.\n\n\
+            Keys:\n  \
+            data                        default data file or directory\n  \
+            srs.initial_ease            ease factor of new items (2.5)\n  \
+            srs.min_ease                ease floor (1.3)\n  \
+            srs.easy_bonus              ease added on easy (0.15)\n  \
+            srs.hard_penalty            ease removed on hard (0.15)\n  \
+            srs.incorrect_penalty       ease removed on incorrect (0.2)\n  \
+            srs.reviewing_interval_days interval for reviewing status (7)\n  \
+            srs.mastered_interval_days  interval for mastered status (30)\n  \
+            srs.mastered_accuracy       accuracy % for mastered status (90)\n  \
+            srs.mastered_min_quizzes    reviews for mastered status (5)\n  \
+            srs.weak_accuracy           weak below this accuracy % (70)\n  \
+            srs.weak_min_quizzes        reviews before an item can be weak (2)\n  \
+            srs.slow_answer_ms          slower correct answers count as hard (0: off)\n\n\
+            show prints the effective merged configuration and where each value came from.",
+        after_help = "EXAMPLES:\n  \
+            ringo-srs config show\n  \
+            ringo-srs config get srs.min_ease\n  \
+            ringo-srs config set srs.mastered_interval_days 21\n  \
+            ringo-srs config set data ~/notes/deck --user"
+    )]
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+
+    /// Render a study progress report as Markdown or HTML
+    #[command(
+        long_about = "Render a human-readable study progress report.\n\n\
+            Covers the period ending now: items added, reviews done and accuracy (compared\n\
+            with the previous period), a per-day accuracy trend, newly mastered items, and\n\
+            the weakest items with their context sentences.\n\
+            Reviews are counted from the per-item review log.\n\
+            With --output the file is written and a summary returned; otherwise the\n\
+            rendered report is returned in the content field.",
+        after_help = "EXAMPLES:\n  \
+            ringo-srs report\n  \
+            ringo-srs report --format html --period month --output data/report.html"
+    )]
+    Report {
+        /// Output format: md or html
+        #[arg(long, value_name = "FORMAT", default_value = "md", value_parser = ["md", "html"])]
+        format: String,
+
+        /// Report period: day, week, or month (30 days)
+        #[arg(long, value_name = "PERIOD", default_value = "week", value_parser = ["day", "week", "month"])]
+        period: String,
+
+        /// Output file (content is returned in JSON if omitted)
+        #[arg(long, value_name = "FILE")]
+        output: Option<PathBuf>,
     },
 
     /// Show learning statistics
@@ -129,9 +681,173 @@ pub enum Command {
         long_about = "Show overall learning statistics.\n\n\
             Returns total items, due count, accuracy percentage,\n\
             breakdowns by status (new/learning/reviewing/mastered)\n\
-            and by type (word/phrase/idiom/grammar), plus next due date.",
+            and by type (word/phrase/idiom/grammar), plus next due date.\n\
+            median_response_ms gives the median answer time per type, over reviews\n\
+            whose response time was recorded.",
         after_help = "EXAMPLE:\n  \
             ringo-srs stats"
     )]
     Stats,
+
+    /// Forecast how many reviews fall due each day
+    #[command(
+        long_about = "Forecast the review workload for the coming days.\n\n\
+            overdue counts items due now. Each day from today (UTC) then lists the items\n\
+            falling due later that day, split by current status and by deck. Decks are\n\
+            item tags; an item with several tags counts toward each, and items without\n\
+            tags are counted as \"untagged\". cumulative is the running total including\n\
+            overdue items, i.e. the backlog if nothing is reviewed until that day.\n\
+            beyond counts items due after the last day.",
+        after_help = "EXAMPLES:\n  \
+            ringo-srs forecast\n  \
+            ringo-srs forecast --days 7"
+    )]
+    Forecast {
+        /// Number of days, starting today
+        #[arg(long, value_name = "N", default_value_t = 30, value_parser = clap::value_parser!(u16).range(1..=365))]
+        days: u16,
+    },
+
+    /// Show study streaks, daily review counts and a calendar heatmap
+    #[command(
+        long_about = "Show study activity from the review log.\n\n\
+            current_streak counts consecutive days with at least one review, ending today\n\
+            (or yesterday, if nothing has been reviewed yet today); longest_streak is the\n\
+            best run ever. daily maps each day of the last year with reviews to its count.\n\
+            today compares today's reviews with --goal. Days are UTC.\n\
+            With --heatmap, heatmap holds the lines of an ASCII calendar of the last year\n\
+            (weeks as columns, Monday first; . none, then - + * # relative to the busiest day).",
+        after_help = "EXAMPLES:\n  \
+            ringo-srs activity\n  \
+            ringo-srs activity --goal 30 --heatmap"
+    )]
+    Activity {
+        /// Daily review goal
+        #[arg(long, value_name = "N", default_value_t = 20)]
+        goal: usize,
+
+        /// Include the calendar heatmap lines in the output
+        #[arg(long)]
+        heatmap: bool,
+    },
+
+    /// Analyze how well the scheduler works from the review history
+    #[command(
+        long_about = "Analyze the review history.\n\n\
+            retention: true retention, i.e. the share of correct answers on reviews that\n\
+            follow an earlier review of the same item (first reviews are excluded).\n\
+            It is broken down by the interval scheduled before the review, by type and by\n\
+            source. Each group also shows the mean recall predicted by the forgetting\n\
+            curve (90% when reviewed exactly when due). The calibration table bins reviews\n\
+            by predicted recall (10-point bins) and compares each bin's mean prediction with\n\
+            its actual retention; brier is the mean squared prediction error.",
+        after_help = "EXAMPLES:\n  \
+            ringo-srs analytics retention\n  \
+            ringo-srs analytics retention --days 90"
+    )]
+    Analytics {
+        #[command(subcommand)]
+        action: AnalyticsAction,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ProfilesAction {
+    /// List profiles and their data files
+    List,
+
+    /// Add a profile (its data file is --data, default data/profiles/NAME.json)
+    Create {
+        /// Profile name (letters, digits, '-' and '_')
+        #[arg(value_name = "NAME")]
+        name: String,
+    },
+
+    /// Remove a profile (its data file is kept unless --purge)
+    Delete {
+        /// Profile name
+        #[arg(value_name = "NAME")]
+        name: String,
+
+        /// Also delete the profile's data file (or directory) and backup
+        #[arg(long)]
+        purge: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigAction {
+    /// Print the effective configuration
+    Show,
+
+    /// Print one effective value
+    Get {
+        /// Dotted key, e.g. srs.min_ease
+        #[arg(value_name = "KEY")]
+        key: String,
+    },
+
+    /// Set a value in the project file (or the user file with --user)
+    Set {
+        /// Dotted key, e.g. srs.min_ease
+        #[arg(value_name = "KEY")]
+        key: String,
+
+        /// New value
+        #[arg(value_name = "VALUE")]
+        value: String,
+
+        /// Write to the user-level file instead of ./ringo-srs.toml
+        #[arg(long)]
+        user: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SessionAction {
+    /// Freeze a question set from due items
+    Start {
+        /// Number of questions
+        #[arg(long, value_name = "N", default_value_t = 10)]
+        n: usize,
+
+        /// Ask for the front given the back
+        #[arg(long)]
+        reverse: bool,
+
+        /// Plan the questions like `plan` (NEW:REVIEW:WEAK) instead of taking due items in order
+        #[arg(long, value_name = "NEW:REVIEW:WEAK")]
+        mix: Option<Mix>,
+    },
+
+    /// Record the grade for question N
+    Answer {
+        /// Question number (1-based)
+        #[arg(value_name = "N")]
+        n: usize,
+
+        /// Grade: again, hard, good, easy (or 1-4)
+        #[arg(value_name = "GRADE")]
+        grade: String,
+
+        /// Time taken to answer, in milliseconds
+        #[arg(long, value_name = "MS")]
+        response_ms: Option<u64>,
+    },
+
+    /// Show the active session and its unanswered questions
+    Resume,
+
+    /// End the session and summarize it
+    Finish,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum AnalyticsAction {
+    /// True retention by interval, type and source, plus a calibration table
+    Retention {
+        /// Only reviews from the last N days
+        #[arg(long, value_name = "N")]
+        days: Option<u32>,
+    },
 }
@@ -0,0 +1,191 @@
+use crate::dedup::normalize_front;
+use crate::lemma;
+
+/// Placeholder shown in place of the hidden text.
+pub const BLANK: &str = "[...]";
+
+/// A cloze deletion: the sentence with a blank and the hidden text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cloze {
+    pub text: String,
+    pub answer: String,
+}
+
+/// Cloze deletions for `front` in a context sentence.
+///
+/// Explicit `{{c1::text}}` / `{{c1::text::hint}}` markers take precedence: one
+/// deletion per marker number, with the other markers shown as plain text and
+/// the hint (if any) shown in the blank. Without markers the front is blanked
+/// where it occurs as whole words, or failing that where a run of words shares
+/// its lemma ("implemented" for "implement", "broke the ice" for "break the ice").
+pub fn clozes(context: &str, front: &str) -> Vec<Cloze> {
+    let markers = parse_markers(context);
+    if markers.iter().any(|part| matches!(part, Part::Marker { .. })) {
+        return from_markers(&markers);
+    }
+    find_front(context, front)
+        .map(|(start, end)| Cloze {
+            text: format!("{}{BLANK}{}", &context[..start], &context[end..]),
+            answer: context[start..end].to_string(),
+        })
+        .into_iter()
+        .collect()
+}
+
+#[derive(Debug)]
+enum Part<'a> {
+    Text(&'a str),
+    Marker { number: u32, text: &'a str, hint: Option<&'a str> },
+}
+
+fn parse_markers(context: &str) -> Vec<Part<'_>> {
+    let mut parts = Vec::new();
+    let mut rest = context;
+    while let Some(open) = rest.find("{{c") {
+        let parsed = rest[open + 3..].find("}}").and_then(|close| {
+            let inner = &rest[open + 3..open + 3 + close];
+            let (number, body) = inner.split_once("::")?;
+            let number: u32 = number.parse().ok()?;
+            let (text, hint) = match body.split_once("::") {
+                Some((text, hint)) => (text, Some(hint)),
+                None => (body, None),
+            };
+            Some((Part::Marker { number, text, hint }, open + 3 + close + 2))
+        });
+        match parsed {
+            Some((marker, end)) => {
+                if open > 0 {
+                    parts.push(Part::Text(&rest[..open]));
+                }
+                parts.push(marker);
+                rest = &rest[end..];
+            }
+            None => {
+                // Not a marker: keep "{{c" as text and continue after it
+                parts.push(Part::Text(&rest[..open + 3]));
+                rest = &rest[open + 3..];
+            }
+        }
+    }
+    if !rest.is_empty() {
+        parts.push(Part::Text(rest));
+    }
+    parts
+}
+
+fn from_markers(parts: &[Part]) -> Vec<Cloze> {
+    let mut numbers: Vec<u32> = parts
+        .iter()
+        .filter_map(|part| match part {
+            Part::Marker { number, .. } => Some(*number),
+            Part::Text(_) => None,
+        })
+        .collect();
+    numbers.sort_unstable();
+    numbers.dedup();
+
+    numbers
+        .into_iter()
+        .map(|n| {
+            let mut text = String::new();
+            let mut answers = Vec::new();
+            for part in parts {
+                match part {
+                    Part::Text(t) => text.push_str(t),
+                    Part::Marker { number, text: t, hint } if *number == n => {
+                        answers.push(*t);
+                        match hint {
+                            Some(hint) => text.push_str(&format!("[{hint}]")),
+                            None => text.push_str(BLANK),
+                        }
+                    }
+                    Part::Marker { text: t, .. } => text.push_str(t),
+                }
+            }
+            Cloze {
+                text,
+                answer: answers.join(" / "),
+            }
+        })
+        .collect()
+}
+
+/// Byte range of the front inside the context.
+fn find_front(context: &str, front: &str) -> Option<(usize, usize)> {
+    let words = word_spans(context);
+    let front_words = normalize_front(front).split_whitespace().count();
+    if front_words == 0 {
+        return None;
+    }
+
+    // Whole-word, case-insensitive match of the normalized front
+    let key = normalize_front(front);
+    for window in words.windows(front_words) {
+        let (start, end) = (window[0].0, window[front_words - 1].1);
+        let candidate: Vec<&str> = window.iter().map(|&(s, e)| &context[s..e]).collect();
+        if candidate.join(" ").to_lowercase() == key {
+            return Some((start, end));
+        }
+    }
+
+    // Inflected forms
+    words.windows(front_words).find_map(|window| {
+        let (start, end) = (window[0].0, window[front_words - 1].1);
+        let candidate: Vec<&str> = window.iter().map(|&(s, e)| &context[s..e]).collect();
+        lemma::shared_lemma(front, &candidate.join(" ")).map(|_| (start, end))
+    })
+}
+
+/// Byte spans of words (letters, digits, apostrophes and hyphens).
+fn word_spans(text: &str) -> Vec<(usize, usize)> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '\'' || c == '-';
+    let mut spans = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        match (is_word(c), start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                spans.push((s, i));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        spans.push((s, text.len()));
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blank_front() {
+        let c = clozes("We need to implement the feature.", "implement");
+        assert_eq!(c[0].text, "We need to [...] the feature.");
+        assert_eq!(c[0].answer, "implement");
+    }
+
+    #[test]
+    fn test_blank_inflected_and_case() {
+        let c = clozes("She broke the ice with a joke.", "break the ice");
+        assert_eq!(c[0].text, "She [...] with a joke.");
+        assert_eq!(c[0].answer, "broke the ice");
+        let c = clozes("Implemented by Friday.", "to implement");
+        assert_eq!(c[0].answer, "Implemented");
+        assert!(clozes("Nothing here.", "implement").is_empty());
+    }
+
+    #[test]
+    fn test_markers() {
+        let c = clozes("{{c1::Apparently}}, he is {{c2::busy::adj}}.", "apparently");
+        assert_eq!(c.len(), 2);
+        assert_eq!(c[0].text, "[...], he is busy.");
+        assert_eq!(c[0].answer, "Apparently");
+        assert_eq!(c[1].text, "Apparently, he is [adj].");
+        let c = clozes("Not a {{c marker}} at all.", "marker");
+        assert_eq!(c[0].text, "Not a {{c [...]}} at all.");
+    }
+}
@@ -0,0 +1,338 @@
+use crate::error::AppError;
+use crate::sm2;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml_edit::{value, DocumentMut, Item, Table, Value};
+
+/// Project-local configuration file, looked up in the current directory.
+pub const CONFIG_FILE: &str = "ringo-srs.toml";
+/// Environment variable overriding the configuration file path.
+pub const CONFIG_ENV: &str = "RINGO_SRS_CONFIG";
+/// Environment variable overriding the user-level configuration file path.
+pub const USER_CONFIG_ENV: &str = "RINGO_SRS_USER_CONFIG";
+/// Environment variable setting the data path (same as `--data`).
+pub const DATA_ENV: &str = "RINGO_SRS_DATA";
+/// Environment variable selecting a profile (same as `--profile`).
+pub const PROFILE_ENV: &str = "RINGO_SRS_PROFILE";
+/// Data path used when neither `--data`, `--profile` nor a config file sets one.
+pub const DEFAULT_DATA: &str = "data/learning-items.json";
+
+/// Path of the configuration file: `$RINGO_SRS_CONFIG` or `./ringo-srs.toml`.
+pub fn config_path() -> PathBuf {
+    std::env::var_os(CONFIG_ENV)
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(CONFIG_FILE))
+}
+
+/// Profile named by `RINGO_SRS_PROFILE`, if set.
+pub fn env_profile() -> Option<String> {
+    std::env::var(PROFILE_ENV).ok().filter(|p| !p.is_empty())
+}
+
+/// Path of the user-level configuration file: `$RINGO_SRS_USER_CONFIG`, else
+/// `$XDG_CONFIG_HOME/ringo-srs/config.toml`, else `~/.config/ringo-srs/config.toml`.
+pub fn user_config_path() -> Option<PathBuf> {
+    let env = |name| std::env::var_os(name).filter(|p| !p.is_empty()).map(PathBuf::from);
+    if let Some(path) = env(USER_CONFIG_ENV) {
+        return Some(path);
+    }
+    let base = env("XDG_CONFIG_HOME").or_else(|| env("HOME").map(|home| home.join(".config")))?;
+    Some(base.join("ringo-srs").join("config.toml"))
+}
+
+/// Effective configuration: built-in defaults, overridden in turn by the
+/// user-level file, the project file, `RINGO_SRS_DATA`, then `--data` or
+/// `--profile` (an explicit `--data` with `--profile` is an error).
+#[derive(Debug, Clone, Serialize)]
+pub struct Config {
+    /// Data file or directory
+    pub data: PathBuf,
+    /// Scheduler parameters and status thresholds (`[srs]` table)
+    pub srs: sm2::Params,
+    /// Where each key's value came from: "default", "user", "project", "env", "flag" or "profile"
+    #[serde(skip)]
+    pub origins: BTreeMap<String, &'static str>,
+}
+
+impl Config {
+    pub fn load(data_flag: Option<&Path>, profile: Option<&str>) -> Result<Config, AppError> {
+        let defaults = serde_json::to_value(sm2::Params::default())?;
+        let mut srs = defaults.as_object().cloned().unwrap_or_default();
+        let mut data = PathBuf::from(DEFAULT_DATA);
+        let mut origins: BTreeMap<String, &'static str> =
+            srs.keys().map(|k| (format!("srs.{k}"), "default")).collect();
+        origins.insert("data".to_string(), "default");
+
+        let files = config_files()?;
+        for (origin, file) in &files {
+            for (key, value) in file.settings()? {
+                match key.strip_prefix("srs.") {
+                    Some(name) => {
+                        srs.insert(name.to_string(), value);
+                    }
+                    None => {
+                        data = value.as_str().map(|d| file.resolve(d)).unwrap_or(data);
+                    }
+                }
+                origins.insert(key, *origin);
+            }
+        }
+
+        let srs: sm2::Params = serde_json::from_value(serde_json::Value::Object(srs))
+            .map_err(|e| AppError::InvalidInput(format!("Invalid [srs] configuration: {e}")))?;
+        srs.validate()
+            .map_err(|e| AppError::InvalidInput(format!("Invalid [srs] configuration: {e}")))?;
+
+        if data_flag.is_some() && profile.is_some() {
+            return Err(AppError::InvalidInput(
+                "--data and --profile cannot be used together".to_string(),
+            ));
+        }
+        // Flags beat the environment; a profile from the environment beats RINGO_SRS_DATA
+        let env_data = std::env::var_os(DATA_ENV).filter(|p| !p.is_empty()).map(PathBuf::from);
+        if let Some(flag) = data_flag {
+            data = flag.to_path_buf();
+            origins.insert("data".to_string(), "flag");
+        } else if let Some(name) = profile.map(str::to_string).or_else(env_profile) {
+            data = merged_profiles(&files)
+                .into_iter()
+                .find(|(n, _, _)| *n == name)
+                .map(|(_, path, _)| path)
+                .ok_or_else(|| AppError::InvalidInput(format!("Unknown profile '{name}'")))?;
+            origins.insert("data".to_string(), "profile");
+        } else if let Some(env) = env_data {
+            data = env;
+            origins.insert("data".to_string(), "env");
+        }
+
+        Ok(Config { data, srs, origins })
+    }
+
+    /// Flattened `key -> value` view, using the same dotted keys as `config get/set`.
+    pub fn to_flat_json(&self) -> serde_json::Map<String, serde_json::Value> {
+        let mut map = serde_json::Map::new();
+        map.insert("data".to_string(), serde_json::json!(self.data.display().to_string()));
+        if let Ok(serde_json::Value::Object(srs)) = serde_json::to_value(&self.srs) {
+            for (k, v) in srs {
+                map.insert(format!("srs.{k}"), v);
+            }
+        }
+        map
+    }
+}
+
+/// Existing configuration files as (origin, file), user-level first.
+fn config_files() -> Result<Vec<(&'static str, ConfigFile)>, AppError> {
+    let mut files = Vec::new();
+    for (origin, path) in [("user", user_config_path()), ("project", Some(config_path()))] {
+        if let Some(path) = path.filter(|p| p.exists()) {
+            files.push((origin, ConfigFile::load(&path)?));
+        }
+    }
+    Ok(files)
+}
+
+/// Profiles of both files as (name, data path, origin), in file order; a
+/// project profile replaces a user-level one of the same name.
+fn merged_profiles(files: &[(&'static str, ConfigFile)]) -> Vec<(String, PathBuf, &'static str)> {
+    let mut merged: Vec<(String, PathBuf, &'static str)> = Vec::new();
+    for (origin, file) in files {
+        for (name, data) in file.profiles() {
+            match merged.iter_mut().find(|(n, _, _)| *n == name) {
+                Some(entry) => *entry = (name, data, *origin),
+                None => merged.push((name, data, *origin)),
+            }
+        }
+    }
+    merged
+}
+
+/// Profiles from the user-level and project configuration files.
+pub fn profiles() -> Result<Vec<(String, PathBuf, &'static str)>, AppError> {
+    Ok(merged_profiles(&config_files()?))
+}
+
+/// A TOML configuration file, edited in place so comments and layout survive.
+pub struct ConfigFile {
+    path: PathBuf,
+    doc: DocumentMut,
+}
+
+impl ConfigFile {
+    /// Load the file, or start an empty one if it doesn't exist.
+    pub fn load(path: &Path) -> Result<Self, AppError> {
+        let doc = if path.exists() {
+            fs::read_to_string(path)?
+                .parse::<DocumentMut>()
+                .map_err(|e| AppError::InvalidInput(format!("{}: {e}", path.display())))?
+        } else {
+            DocumentMut::new()
+        };
+        Ok(ConfigFile { path: path.to_path_buf(), doc })
+    }
+
+    pub fn save(&self) -> Result<(), AppError> {
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, self.doc.to_string())?;
+        Ok(())
+    }
+
+    /// Profiles as (name, data path), in file order. Relative data paths are
+    /// resolved against the directory holding the configuration file.
+    pub fn profiles(&self) -> Vec<(String, PathBuf)> {
+        let Some(table) = self.doc.get("profiles").and_then(Item::as_table) else {
+            return Vec::new();
+        };
+        table
+            .iter()
+            .filter_map(|(name, entry)| {
+                let data = entry.get("data")?.as_str()?;
+                Some((name.to_string(), self.resolve(data)))
+            })
+            .collect()
+    }
+
+    pub fn profile(&self, name: &str) -> Option<PathBuf> {
+        self.profiles().into_iter().find(|(n, _)| n == name).map(|(_, p)| p)
+    }
+
+    /// Add a `[profiles.<name>]` table pointing at `data` (stored as given).
+    pub fn add_profile(&mut self, name: &str, data: &str) {
+        let profiles = self.doc.entry("profiles").or_insert_with(|| {
+            let mut t = Table::new();
+            t.set_implicit(true);
+            Item::Table(t)
+        });
+        let mut entry = Table::new();
+        entry.insert("data", value(data));
+        if let Some(table) = profiles.as_table_mut() {
+            table.insert(name, Item::Table(entry));
+        }
+    }
+
+    /// Remove a profile; returns whether it existed.
+    pub fn remove_profile(&mut self, name: &str) -> bool {
+        self.doc
+            .get_mut("profiles")
+            .and_then(Item::as_table_mut)
+            .is_some_and(|t| t.remove(name).is_some())
+    }
+
+    /// Configuration keys set in this file, as dotted keys with JSON values.
+    /// The `[profiles]` table is not part of the settings.
+    pub fn settings(&self) -> Result<Vec<(String, serde_json::Value)>, AppError> {
+        let mut out = Vec::new();
+        for (key, item) in self.doc.iter() {
+            match (key, item) {
+                ("profiles", _) => {}
+                ("srs", Item::Table(table)) => {
+                    for (name, item) in table.iter() {
+                        let key = format!("srs.{name}");
+                        let value = item.as_value().and_then(to_json).ok_or_else(|| self.invalid(&key))?;
+                        out.push((key, value));
+                    }
+                }
+                ("data", Item::Value(Value::String(s))) => {
+                    out.push(("data".to_string(), serde_json::json!(s.value())))
+                }
+                _ => return Err(self.invalid(key)),
+            }
+        }
+        Ok(out)
+    }
+
+    /// Set a dotted key (`data` or `srs.<name>`).
+    pub fn set(&mut self, key: &str, val: Value) {
+        match key.split_once('.') {
+            Some((table, name)) => {
+                let entry = self.doc.entry(table).or_insert_with(|| Item::Table(Table::new()));
+                if let Some(table) = entry.as_table_mut() {
+                    table.insert(name, Item::Value(val));
+                }
+            }
+            None => {
+                self.doc.insert(key, Item::Value(val));
+            }
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn invalid(&self, key: &str) -> AppError {
+        AppError::InvalidInput(format!("{}: unknown or invalid config key '{key}'", self.path.display()))
+    }
+
+    fn resolve(&self, data: &str) -> PathBuf {
+        let data = Path::new(data);
+        match self.path.parent() {
+            Some(dir) if data.is_relative() && !dir.as_os_str().is_empty() => dir.join(data),
+            _ => data.to_path_buf(),
+        }
+    }
+}
+
+fn to_json(value: &Value) -> Option<serde_json::Value> {
+    match value {
+        Value::Integer(i) => Some(serde_json::json!(*i.value())),
+        Value::Float(f) => Some(serde_json::json!(*f.value())),
+        _ => None,
+    }
+}
+
+/// Profile names are restricted to letters, digits, `-` and `_`.
+pub fn validate_profile_name(name: &str) -> Result<(), AppError> {
+    if !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        Ok(())
+    } else {
+        Err(AppError::InvalidInput(format!(
+            "Invalid profile name '{name}': use letters, digits, '-' and '_'"
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_profiles_round_trip_keeps_comments() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(CONFIG_FILE);
+        fs::write(&path, "# team settings\n[profiles.bob]\ndata = \"/abs/bob.json\"\n").unwrap();
+
+        let mut config = ConfigFile::load(&path).unwrap();
+        config.add_profile("alice", "data/alice.json");
+        config.save().unwrap();
+
+        let config = ConfigFile::load(&path).unwrap();
+        let profiles = config.profiles();
+        assert_eq!(profiles[0], ("bob".to_string(), PathBuf::from("/abs/bob.json")));
+        assert_eq!(profiles[1].1, dir.path().join("data/alice.json"));
+        assert!(fs::read_to_string(&path).unwrap().starts_with("# team settings"));
+    }
+
+    #[test]
+    fn test_remove_profile() {
+        let dir = TempDir::new().unwrap();
+        let mut config = ConfigFile::load(&dir.path().join(CONFIG_FILE)).unwrap();
+        config.add_profile("alice", "a.json");
+        assert!(config.remove_profile("alice"));
+        assert!(!config.remove_profile("alice"));
+        assert!(config.profiles().is_empty());
+    }
+
+    #[test]
+    fn test_validate_profile_name() {
+        assert!(validate_profile_name("alice_2").is_ok());
+        assert!(validate_profile_name("../etc").is_err());
+        assert!(validate_profile_name("").is_err());
+    }
+}
@@ -0,0 +1,106 @@
+use crate::error::AppError;
+use argon2::Argon2;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+
+/// Environment variable holding the passphrase.
+pub const PASSPHRASE_ENV: &str = "RINGO_SRS_PASSPHRASE";
+/// Environment variable naming a keyfile whose contents are the passphrase.
+pub const KEYFILE_ENV: &str = "RINGO_SRS_KEYFILE";
+
+/// Encrypted files start with this marker, followed by the salt, nonce and ciphertext.
+const MAGIC: &[u8] = b"RINGOSRS-ENC1\n";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+/// Read the passphrase from `RINGO_SRS_PASSPHRASE`, or else from the file named
+/// by `RINGO_SRS_KEYFILE` (a trailing newline is ignored).
+pub fn passphrase() -> Result<Vec<u8>, AppError> {
+    if let Some(pass) = std::env::var_os(PASSPHRASE_ENV).filter(|p| !p.is_empty()) {
+        return Ok(pass.into_encoded_bytes());
+    }
+    if let Some(path) = std::env::var_os(KEYFILE_ENV).filter(|p| !p.is_empty()) {
+        let mut key = std::fs::read(&path).map_err(|e| {
+            AppError::Encryption(format!("Cannot read keyfile {}: {e}", path.to_string_lossy()))
+        })?;
+        while key.last().is_some_and(|b| *b == b'\n' || *b == b'\r') {
+            key.pop();
+        }
+        if key.is_empty() {
+            return Err(AppError::Encryption("Keyfile is empty".to_string()));
+        }
+        return Ok(key);
+    }
+    Err(AppError::Encryption(format!(
+        "Data file is encrypted: set {PASSPHRASE_ENV} or {KEYFILE_ENV}"
+    )))
+}
+
+/// Encrypt with ChaCha20-Poly1305 under a key derived from the passphrase by Argon2id.
+/// A fresh salt and nonce are used for every call.
+pub fn encrypt(plaintext: &[u8], passphrase: &[u8]) -> Result<Vec<u8>, AppError> {
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut salt);
+    OsRng.fill_bytes(&mut nonce);
+
+    let cipher = cipher(passphrase, &salt)?;
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), plaintext)
+        .map_err(|_| AppError::Encryption("Encryption failed".to_string()))?;
+
+    let mut out = Vec::with_capacity(MAGIC.len() + SALT_LEN + NONCE_LEN + ciphertext.len());
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&salt);
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+/// Decrypt data produced by [`encrypt`]. Fails on a wrong passphrase or any tampering.
+pub fn decrypt(data: &[u8], passphrase: &[u8]) -> Result<Vec<u8>, AppError> {
+    let body = data
+        .strip_prefix(MAGIC)
+        .filter(|b| b.len() >= SALT_LEN + NONCE_LEN)
+        .ok_or_else(|| AppError::Encryption("Not an encrypted data file".to_string()))?;
+    let (salt, rest) = body.split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+
+    cipher(passphrase, salt)?
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| AppError::Encryption("Decryption failed: wrong passphrase or corrupted file".to_string()))
+}
+
+fn cipher(passphrase: &[u8], salt: &[u8]) -> Result<ChaCha20Poly1305, AppError> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase, salt, &mut key)
+        .map_err(|e| AppError::Encryption(format!("Key derivation failed: {e}")))?;
+    Ok(ChaCha20Poly1305::new(Key::from_slice(&key)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let data = encrypt(b"{\"items\": []}", b"secret").unwrap();
+        assert!(is_encrypted(&data));
+        assert_eq!(decrypt(&data, b"secret").unwrap(), b"{\"items\": []}");
+    }
+
+    #[test]
+    fn test_wrong_passphrase_and_tampering() {
+        let mut data = encrypt(b"hello", b"secret").unwrap();
+        assert!(decrypt(&data, b"wrong").is_err());
+        let last = data.len() - 1;
+        data[last] ^= 1;
+        assert!(decrypt(&data, b"secret").is_err());
+    }
+}
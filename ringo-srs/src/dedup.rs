@@ -0,0 +1,105 @@
+use crate::models::LearningItem;
+use unicode_normalization::UnicodeNormalization;
+
+/// Similarity (normalized edit distance) at or above which two fronts are
+/// reported as near duplicates.
+pub const NEAR_DUPLICATE_THRESHOLD: f64 = 0.85;
+
+const LEADING_WORDS: &[&str] = &["to", "a", "an", "the"];
+
+/// Normalize a front for duplicate comparison.
+///
+/// Applies Unicode NFKC, lowercases, collapses whitespace, strips trailing
+/// punctuation and drops a leading "to" and/or article:
+/// "To break  the ice." → "break the ice".
+pub fn normalize_front(front: &str) -> String {
+    let folded: String = front.nfkc().collect::<String>().to_lowercase();
+    let mut words: Vec<&str> = folded.split_whitespace().collect();
+
+    // Strip trailing punctuation from the last word (may empty it)
+    while let Some(last) = words.last_mut() {
+        let trimmed = last.trim_end_matches(|c: char| c.is_ascii_punctuation() || "。、！？…".contains(c));
+        if trimmed.is_empty() {
+            words.pop();
+        } else {
+            *last = trimmed;
+            break;
+        }
+    }
+
+    // Drop "to" (infinitive) then an article, keeping at least one word
+    for leading in [&LEADING_WORDS[..1], &LEADING_WORDS[1..]] {
+        if words.len() > 1 && leading.contains(&words[0]) {
+            words.remove(0);
+        }
+    }
+
+    words.join(" ")
+}
+
+/// Find an existing item whose normalized front equals that of `front`.
+/// Related forms are not matched: a linked form has its own card.
+pub fn find_duplicate<'a>(items: &'a [LearningItem], front: &str) -> Option<&'a LearningItem> {
+    let key = normalize_front(front);
+    items.iter().find(|item| normalize_front(&item.front) == key)
+}
+
+/// Items whose normalized front is similar to, but not the same as, `front`.
+/// Returns (item, similarity) pairs, most similar first.
+pub fn near_duplicates<'a>(items: &'a [LearningItem], front: &str) -> Vec<(&'a LearningItem, f64)> {
+    let key = normalize_front(front);
+    let mut found: Vec<(&LearningItem, f64)> = items
+        .iter()
+        .filter_map(|item| {
+            let other = normalize_front(&item.front);
+            if other == key {
+                return None;
+            }
+            let similarity = strsim::normalized_levenshtein(&key, &other);
+            (similarity >= NEAR_DUPLICATE_THRESHOLD).then_some((item, similarity))
+        })
+        .collect();
+    found.sort_by(|a, b| b.1.total_cmp(&a.1));
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_variants_collapse() {
+        let expected = "break the ice";
+        assert_eq!(normalize_front("Break the ice"), expected);
+        assert_eq!(normalize_front("break the ice."), expected);
+        assert_eq!(normalize_front("to break the ice"), expected);
+        assert_eq!(normalize_front("break  the ice"), expected);
+        assert_eq!(normalize_front("  To Break the Ice!! "), expected);
+    }
+
+    #[test]
+    fn test_normalize_leading_article() {
+        assert_eq!(normalize_front("a piece of cake"), "piece of cake");
+        assert_eq!(normalize_front("The more, the merrier"), "more, the merrier");
+    }
+
+    #[test]
+    fn test_normalize_keeps_single_word() {
+        assert_eq!(normalize_front("to"), "to");
+        assert_eq!(normalize_front("the"), "the");
+    }
+
+    #[test]
+    fn test_normalize_nfkc_full_width() {
+        assert_eq!(normalize_front("ｂｒｅａｋ　ｔｈｅ　ｉｃｅ．"), "break the ice");
+    }
+
+    #[test]
+    fn test_similarity_threshold() {
+        let a = normalize_front("implement");
+        let b = normalize_front("implements");
+        assert!(strsim::normalized_levenshtein(&a, &b) >= NEAR_DUPLICATE_THRESHOLD);
+        let c = normalize_front("improve");
+        assert!(strsim::normalized_levenshtein(&a, &c) < NEAR_DUPLICATE_THRESHOLD);
+    }
+}
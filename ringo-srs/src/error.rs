@@ -2,8 +2,8 @@ use std::fmt;
 
 #[derive(Debug, thiserror::Error)]
 pub enum AppError {
-    #[error("Duplicate item: front '{0}' already exists")]
-    Duplicate(String),
+    #[error("Duplicate item: front '{front}' already exists as {existing_id}")]
+    Duplicate { front: String, existing_id: String },
 
     #[error("Item '{0}' not found")]
     NotFound(String),
@@ -17,6 +17,9 @@ pub enum AppError {
     #[error("Invalid input: {0}")]
     InvalidInput(String),
 
+    #[error("Encryption error: {0}")]
+    Encryption(String),
+
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
 
@@ -27,21 +30,26 @@ pub enum AppError {
 impl AppError {
     pub fn code(&self) -> &'static str {
         match self {
-            AppError::Duplicate(_) => "duplicate",
+            AppError::Duplicate { .. } => "duplicate",
             AppError::NotFound(_) => "not_found",
             AppError::AmbiguousId(_) => "ambiguous_id",
             AppError::NoDataFile(_) => "no_data_file",
             AppError::InvalidInput(_) => "invalid_input",
+            AppError::Encryption(_) => "encryption_error",
             AppError::IoError(_) | AppError::JsonError(_) => "io_error",
         }
     }
 
     pub fn to_json(&self) -> serde_json::Value {
-        serde_json::json!({
+        let mut json = serde_json::json!({
             "ok": false,
             "error": self.code(),
             "message": self.to_string()
-        })
+        });
+        if let AppError::Duplicate { existing_id, .. } = self {
+            json["existing_id"] = serde_json::json!(existing_id);
+        }
+        json
     }
 }
 
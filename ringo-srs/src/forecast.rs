@@ -0,0 +1,104 @@
+use crate::models::LearningItem;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use std::collections::BTreeMap;
+
+/// Deck name counted for items without tags.
+pub const UNTAGGED: &str = "untagged";
+
+/// Due items in one bucket, split by status and by deck (tag).
+#[derive(Debug, Default)]
+pub struct Bucket<'a> {
+    pub due: usize,
+    pub by_status: BTreeMap<&'a str, usize>,
+    /// An item counts once for each of its tags
+    pub by_deck: BTreeMap<&'a str, usize>,
+}
+
+impl<'a> Bucket<'a> {
+    fn add(&mut self, item: &'a LearningItem) {
+        self.due += 1;
+        *self.by_status.entry(item.status.as_str()).or_default() += 1;
+        if item.tags.is_empty() {
+            *self.by_deck.entry(UNTAGGED).or_default() += 1;
+        }
+        for tag in &item.tags {
+            *self.by_deck.entry(tag.as_str()).or_default() += 1;
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Forecast<'a> {
+    /// Due now (next review at or before `now`)
+    pub overdue: Bucket<'a>,
+    /// Falling due later on each UTC day, starting today
+    pub days: Vec<(NaiveDate, Bucket<'a>)>,
+    /// Due after the last day
+    pub beyond: usize,
+}
+
+/// Count upcoming reviews over `days` days starting today (UTC).
+pub fn build(items: &[LearningItem], days: usize, now: DateTime<Utc>) -> Forecast<'_> {
+    let today = now.date_naive();
+    let mut forecast = Forecast {
+        overdue: Bucket::default(),
+        days: (0..days)
+            .map(|d| (today + Duration::days(d as i64), Bucket::default()))
+            .collect(),
+        beyond: 0,
+    };
+    for item in items {
+        if item.next_review <= now {
+            forecast.overdue.add(item);
+            continue;
+        }
+        let offset = (item.next_review.date_naive() - today).num_days() as usize;
+        match forecast.days.get_mut(offset) {
+            Some((_, bucket)) => bucket.add(item),
+            None => forecast.beyond += 1,
+        }
+    }
+    forecast
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn item(next_review: DateTime<Utc>, status: &str, tags: &[&str]) -> LearningItem {
+        LearningItem {
+            next_review,
+            status: status.to_string(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            ..LearningItem::test("implement")
+        }
+    }
+
+    #[test]
+    fn test_forecast_buckets() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 10, 9, 0, 0).unwrap();
+        let at = |d: u32, h: u32| Utc.with_ymd_and_hms(2026, 3, d, h, 0, 0).unwrap();
+        let items = vec![
+            item(at(1, 0), "learning", &["toeic"]),
+            item(at(10, 8), "new", &[]),
+            item(at(10, 20), "learning", &["toeic", "work"]),
+            item(at(12, 0), "reviewing", &["work"]),
+            item(at(20, 0), "mastered", &[]),
+        ];
+        let f = build(&items, 3, now);
+
+        assert_eq!(f.overdue.due, 2);
+        assert_eq!(f.overdue.by_status["new"], 1);
+        assert_eq!(f.overdue.by_deck[UNTAGGED], 1);
+        assert_eq!(f.days.len(), 3);
+        assert_eq!(f.days[0].0, now.date_naive());
+        // Later today, counted under both of its decks
+        assert_eq!(f.days[0].1.due, 1);
+        assert_eq!(f.days[0].1.by_deck["toeic"], 1);
+        assert_eq!(f.days[0].1.by_deck["work"], 1);
+        assert_eq!(f.days[1].1.due, 0);
+        assert_eq!(f.days[2].1.by_status["reviewing"], 1);
+        assert_eq!(f.beyond, 1);
+    }
+}
@@ -0,0 +1,390 @@
+use crate::dedup::normalize_front;
+use crate::models::LearningItem;
+use std::collections::HashSet;
+
+/// Shortest stem a suffix rule may leave ("seed" is not "se" + "ed").
+const MIN_STEM: usize = 3;
+
+/// Irregular verbs: (base, past, past participle).
+const IRREGULAR_VERBS: &[(&str, &str, &str)] = &[
+    ("arise", "arose", "arisen"),
+    ("awake", "awoke", "awoken"),
+    ("bear", "bore", "borne"),
+    ("beat", "beat", "beaten"),
+    ("become", "became", "become"),
+    ("begin", "began", "begun"),
+    ("bend", "bent", "bent"),
+    ("bet", "bet", "bet"),
+    ("bind", "bound", "bound"),
+    ("bite", "bit", "bitten"),
+    ("bleed", "bled", "bled"),
+    ("blow", "blew", "blown"),
+    ("break", "broke", "broken"),
+    ("breed", "bred", "bred"),
+    ("bring", "brought", "brought"),
+    ("build", "built", "built"),
+    ("burn", "burnt", "burnt"),
+    ("burst", "burst", "burst"),
+    ("buy", "bought", "bought"),
+    ("catch", "caught", "caught"),
+    ("choose", "chose", "chosen"),
+    ("cling", "clung", "clung"),
+    ("come", "came", "come"),
+    ("cost", "cost", "cost"),
+    ("creep", "crept", "crept"),
+    ("cut", "cut", "cut"),
+    ("deal", "dealt", "dealt"),
+    ("dig", "dug", "dug"),
+    ("do", "did", "done"),
+    ("draw", "drew", "drawn"),
+    ("dream", "dreamt", "dreamt"),
+    ("drink", "drank", "drunk"),
+    ("drive", "drove", "driven"),
+    ("eat", "ate", "eaten"),
+    ("fall", "fell", "fallen"),
+    ("feed", "fed", "fed"),
+    ("feel", "felt", "felt"),
+    ("fight", "fought", "fought"),
+    ("find", "found", "found"),
+    ("flee", "fled", "fled"),
+    ("fling", "flung", "flung"),
+    ("fly", "flew", "flown"),
+    ("forbid", "forbade", "forbidden"),
+    ("forget", "forgot", "forgotten"),
+    ("forgive", "forgave", "forgiven"),
+    ("freeze", "froze", "frozen"),
+    ("get", "got", "gotten"),
+    ("give", "gave", "given"),
+    ("go", "went", "gone"),
+    ("grind", "ground", "ground"),
+    ("grow", "grew", "grown"),
+    ("hang", "hung", "hung"),
+    ("have", "had", "had"),
+    ("hear", "heard", "heard"),
+    ("hide", "hid", "hidden"),
+    ("hit", "hit", "hit"),
+    ("hold", "held", "held"),
+    ("hurt", "hurt", "hurt"),
+    ("keep", "kept", "kept"),
+    ("kneel", "knelt", "knelt"),
+    ("know", "knew", "known"),
+    ("lay", "laid", "laid"),
+    ("lead", "led", "led"),
+    ("lean", "leant", "leant"),
+    ("leap", "leapt", "leapt"),
+    ("learn", "learnt", "learnt"),
+    ("leave", "left", "left"),
+    ("lend", "lent", "lent"),
+    ("let", "let", "let"),
+    ("lie", "lay", "lain"),
+    ("light", "lit", "lit"),
+    ("lose", "lost", "lost"),
+    ("make", "made", "made"),
+    ("mean", "meant", "meant"),
+    ("meet", "met", "met"),
+    ("mislead", "misled", "misled"),
+    ("mistake", "mistook", "mistaken"),
+    ("overcome", "overcame", "overcome"),
+    ("pay", "paid", "paid"),
+    ("prove", "proved", "proven"),
+    ("put", "put", "put"),
+    ("quit", "quit", "quit"),
+    ("read", "read", "read"),
+    ("ride", "rode", "ridden"),
+    ("ring", "rang", "rung"),
+    ("rise", "rose", "risen"),
+    ("run", "ran", "run"),
+    ("say", "said", "said"),
+    ("see", "saw", "seen"),
+    ("seek", "sought", "sought"),
+    ("sell", "sold", "sold"),
+    ("send", "sent", "sent"),
+    ("set", "set", "set"),
+    ("shake", "shook", "shaken"),
+    ("shine", "shone", "shone"),
+    ("shoot", "shot", "shot"),
+    ("show", "showed", "shown"),
+    ("shrink", "shrank", "shrunk"),
+    ("shut", "shut", "shut"),
+    ("sing", "sang", "sung"),
+    ("sink", "sank", "sunk"),
+    ("sit", "sat", "sat"),
+    ("sleep", "slept", "slept"),
+    ("slide", "slid", "slid"),
+    ("speak", "spoke", "spoken"),
+    ("spend", "spent", "spent"),
+    ("spin", "spun", "spun"),
+    ("spit", "spat", "spat"),
+    ("split", "split", "split"),
+    ("spread", "spread", "spread"),
+    ("spring", "sprang", "sprung"),
+    ("stand", "stood", "stood"),
+    ("steal", "stole", "stolen"),
+    ("stick", "stuck", "stuck"),
+    ("sting", "stung", "stung"),
+    ("strike", "struck", "struck"),
+    ("swear", "swore", "sworn"),
+    ("sweep", "swept", "swept"),
+    ("swim", "swam", "swum"),
+    ("swing", "swung", "swung"),
+    ("take", "took", "taken"),
+    ("teach", "taught", "taught"),
+    ("tear", "tore", "torn"),
+    ("tell", "told", "told"),
+    ("think", "thought", "thought"),
+    ("throw", "threw", "thrown"),
+    ("understand", "understood", "understood"),
+    ("wake", "woke", "woken"),
+    ("wear", "wore", "worn"),
+    ("win", "won", "won"),
+    ("wind", "wound", "wound"),
+    ("withdraw", "withdrew", "withdrawn"),
+    ("write", "wrote", "written"),
+];
+
+/// Other irregular forms: (form, lemma).
+const IRREGULAR_FORMS: &[(&str, &str)] = &[
+    ("am", "be"),
+    ("are", "be"),
+    ("is", "be"),
+    ("was", "be"),
+    ("were", "be"),
+    ("been", "be"),
+    ("being", "be"),
+    ("has", "have"),
+    ("does", "do"),
+    ("dying", "die"),
+    ("lying", "lie"),
+    ("tying", "tie"),
+    ("children", "child"),
+    ("men", "man"),
+    ("women", "woman"),
+    ("people", "person"),
+    ("mice", "mouse"),
+    ("feet", "foot"),
+    ("teeth", "tooth"),
+    ("geese", "goose"),
+    ("better", "good"),
+    ("best", "good"),
+    ("worse", "bad"),
+    ("worst", "bad"),
+];
+
+/// Words ending in -s that are not plurals or third-person forms.
+const UNINFLECTED: &[&str] = &[
+    "news", "series", "species", "means", "lens", "physics", "mathematics", "economics", "politics",
+];
+
+/// Auxiliaries dropped from the start of a front when followed by an
+/// inflected form ("has gone" → "gone").
+const AUXILIARIES: &[&str] = &[
+    "am", "are", "is", "was", "were", "be", "been", "being", "has", "have", "had", "will",
+    "would", "did", "does", "do",
+];
+
+/// Candidate lemmas for a single lowercase word, including the word itself.
+///
+/// Irregular forms come from the tables above. Regular inflections
+/// (-s, -es, -ies, -ves, -ed, -ied, -ing, doubled consonants) are undone by
+/// suffix rules, but a candidate is only kept when the stripped stem has at
+/// least `MIN_STEM` letters and the candidate is in `known` or is the base of
+/// an irregular verb, so "thing" never yields "the". Learner errors such as
+/// "eated" resolve like regular forms.
+pub fn word_lemmas(word: &str, known: &HashSet<String>) -> Vec<String> {
+    let mut out = vec![word.to_string()];
+    for &(base, past, participle) in IRREGULAR_VERBS {
+        if (word == past || word == participle) && !out.iter().any(|c| c == base) {
+            out.push(base.to_string());
+        }
+    }
+    for &(form, lemma) in IRREGULAR_FORMS {
+        if word == form && !out.iter().any(|c| c == lemma) {
+            out.push(lemma.to_string());
+        }
+    }
+    if UNINFLECTED.contains(&word) {
+        return out;
+    }
+
+    let mut push = |stem: &str, candidate: String| {
+        if stem.len() >= MIN_STEM && is_known(&candidate, known) && !out.contains(&candidate) {
+            out.push(candidate);
+        }
+    };
+    if let Some(stem) = word.strip_suffix("ies").or_else(|| word.strip_suffix("ied")) {
+        push(stem, format!("{stem}y"));
+    }
+    if let Some(stem) = word.strip_suffix("ves") {
+        push(stem, format!("{stem}f"));
+        push(stem, format!("{stem}fe"));
+    }
+    if let Some(stem) = word.strip_suffix("es") {
+        push(stem, stem.to_string());
+    }
+    if let Some(stem) = word.strip_suffix('s') {
+        if !stem.ends_with('s') {
+            push(stem, stem.to_string());
+        }
+    }
+    for suffix in ["ed", "ing"] {
+        if let Some(stem) = word.strip_suffix(suffix) {
+            push(stem, stem.to_string());
+            push(stem, format!("{stem}e"));
+            if let Some(undoubled) = undouble(stem) {
+                push(stem, undoubled);
+            }
+        }
+    }
+
+    out
+}
+
+/// A candidate base form counts when it is a known word or an irregular verb.
+fn is_known(candidate: &str, known: &HashSet<String>) -> bool {
+    known.contains(candidate) || IRREGULAR_VERBS.iter().any(|&(base, _, _)| base == candidate)
+}
+
+/// "stopp" → "stop", "runn" → "run"
+fn undouble(stem: &str) -> Option<String> {
+    let bytes = stem.as_bytes();
+    let n = bytes.len();
+    if n >= 3 && bytes[n - 1] == bytes[n - 2] && !b"aeiousl".contains(&bytes[n - 1]) {
+        Some(stem[..n - 1].to_string())
+    } else {
+        None
+    }
+}
+
+fn is_inflected(word: &str, known: &HashSet<String>) -> bool {
+    word_lemmas(word, known).len() > 1
+}
+
+/// Words of the normalized fronts, for use as `known` base forms.
+fn vocabulary<'a>(fronts: impl IntoIterator<Item = &'a str>) -> HashSet<String> {
+    fronts
+        .into_iter()
+        .flat_map(|front| normalize_front(front).split(' ').map(str::to_string).collect::<Vec<_>>())
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+/// Per-word candidate lemmas of a normalized front, with a leading
+/// auxiliary removed when the next word is an inflected form.
+fn front_lemmas(front: &str, known: &HashSet<String>) -> Vec<Vec<String>> {
+    let normalized = normalize_front(front);
+    let mut words: Vec<&str> = normalized.split(' ').filter(|w| !w.is_empty()).collect();
+    while words.len() > 1 && AUXILIARIES.contains(&words[0]) && is_inflected(words[1], known) {
+        words.remove(0);
+    }
+    words.into_iter().map(|w| word_lemmas(w, known)).collect()
+}
+
+/// Shared lemma of two fronts, if every word position has a lemma in common.
+/// Regular suffixes are only undone towards words that appear in either front.
+pub fn shared_lemma(a: &str, b: &str) -> Option<String> {
+    shared_lemma_in(a, b, &vocabulary([a, b]))
+}
+
+fn shared_lemma_in(a: &str, b: &str, known: &HashSet<String>) -> Option<String> {
+    let la = front_lemmas(a, known);
+    let lb = front_lemmas(b, known);
+    if la.is_empty() || la.len() != lb.len() {
+        return None;
+    }
+    let mut shared = Vec::with_capacity(la.len());
+    for (wa, wb) in la.iter().zip(&lb) {
+        // Prefer the shortest common candidate (closest to the base form)
+        let common = wa
+            .iter()
+            .filter(|c| wb.contains(c))
+            .min_by_key(|c| c.len())?;
+        shared.push(common.as_str());
+    }
+    Some(shared.join(" "))
+}
+
+/// Items sharing a lemma with `front` (via their front or a linked related form),
+/// excluding exact normalized duplicates. Returns (item, lemma) pairs.
+///
+/// Any word of an existing front or related form counts as a known base form,
+/// so "studied" matches an item "studies" when "study" is also an item.
+pub fn related_items<'a>(items: &'a [LearningItem], front: &str) -> Vec<(&'a LearningItem, String)> {
+    let key = normalize_front(front);
+    let known = vocabulary(
+        std::iter::once(front).chain(
+            items
+                .iter()
+                .flat_map(|item| std::iter::once(&item.front).chain(&item.related_forms))
+                .map(String::as_str),
+        ),
+    );
+    items
+        .iter()
+        .filter_map(|item| {
+            std::iter::once(&item.front)
+                .chain(&item.related_forms)
+                .filter(|form| normalize_front(form) != key)
+                .find_map(|form| shared_lemma_in(front, form, &known))
+                .map(|lemma| (item, lemma))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_irregular_verbs() {
+        assert_eq!(shared_lemma("went", "go").as_deref(), Some("go"));
+        assert_eq!(shared_lemma("gone", "went").as_deref(), Some("go"));
+        assert_eq!(shared_lemma("ate", "eat").as_deref(), Some("eat"));
+    }
+
+    #[test]
+    fn test_learner_errors_and_regular_forms() {
+        assert_eq!(shared_lemma("eated", "eat").as_deref(), Some("eat"));
+        assert_eq!(shared_lemma("studies", "study").as_deref(), Some("study"));
+        assert_eq!(shared_lemma("stopped", "stop").as_deref(), Some("stop"));
+        assert_eq!(shared_lemma("running", "run").as_deref(), Some("run"));
+        assert_eq!(shared_lemma("liked", "like").as_deref(), Some("like"));
+        assert_eq!(shared_lemma("dying", "die").as_deref(), Some("die"));
+    }
+
+    #[test]
+    fn test_auxiliary_stripped() {
+        assert_eq!(shared_lemma("has gone", "go").as_deref(), Some("go"));
+        assert_eq!(shared_lemma("is running", "to run").as_deref(), Some("run"));
+        // "do" is kept when not followed by an inflected form
+        assert_eq!(shared_lemma("do homework", "homework"), None);
+    }
+
+    #[test]
+    fn test_phrases() {
+        assert_eq!(shared_lemma("broke the ice", "break the ice").as_deref(), Some("break the ice"));
+        assert_eq!(shared_lemma("break the ice", "break the rules"), None);
+    }
+
+    #[test]
+    fn test_unrelated() {
+        assert_eq!(shared_lemma("implement", "improve"), None);
+        assert_eq!(shared_lemma("bed", "be"), None);
+        assert_eq!(shared_lemma("seed", "see"), None);
+        assert_eq!(shared_lemma("thing", "the"), None);
+        assert_eq!(shared_lemma("news", "new"), None);
+        // Irregular bases still count without being in either front
+        assert_eq!(shared_lemma("seeing", "saw").as_deref(), Some("see"));
+    }
+
+    #[test]
+    fn test_related_items_use_known_fronts() {
+        let mut study = LearningItem::test("study");
+        study.id = "study".to_string();
+        let studies = LearningItem::test("studies");
+        let items = vec![studies, study];
+        let related = related_items(&items, "studied");
+        assert_eq!(related.len(), 2);
+        assert_eq!(related[0].1, "study");
+        assert!(related_items(&items[..1], "studied").is_empty());
+    }
+}
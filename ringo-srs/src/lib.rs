@@ -1,18 +1,46 @@
+pub mod activity;
+pub mod analytics;
+pub mod anki;
+pub mod answer;
 pub mod cli;
+pub mod cloze;
+pub mod config;
+pub mod crypto;
+pub mod dedup;
 pub mod error;
+pub mod forecast;
+pub mod lemma;
+pub mod merge;
 pub mod models;
+pub mod planner;
+pub mod print;
+pub mod quiz;
+pub mod report;
+pub mod search;
+pub mod session;
 pub mod sm2;
 pub mod storage;
+pub mod study;
+pub mod table;
 
 use chrono::Utc;
 use cli::Command;
+use config::Config;
 use error::{success_json, AppError};
-use models::{LearningItem, ReviewInput};
+use models::{LearningItem, NewItem, ReviewInput, ReviewRecord};
 use std::collections::HashMap;
 use std::io::Read;
 
 /// Main dispatch: run CLI command, return JSON value for stdout.
 pub fn run(cli: &cli::Cli) -> Result<serde_json::Value, AppError> {
+    if let Command::Profiles { action } = &cli.command {
+        let active = cli.profile.clone().or_else(|| config::env_profile().filter(|_| cli.data.is_none()));
+        return cmd_profiles(action, cli.data.as_deref(), active.as_deref());
+    }
+    let config = Config::load(cli.data.as_deref(), cli.profile.as_deref())?;
+    if let Command::Config { action } = &cli.command {
+        return cmd_config(&config, action, cli.data.as_deref(), cli.profile.as_deref());
+    }
     match &cli.command {
         Command::Add {
             front,
@@ -21,22 +49,106 @@ pub fn run(cli: &cli::Cli) -> Result<serde_json::Value, AppError> {
             context,
             context_ja,
             source,
-        } => cmd_add(
-            &cli.data,
-            front,
-            back,
-            item_type.as_deref(),
-            context.as_deref(),
-            context_ja.as_deref(),
-            source.as_deref(),
+            force,
+            link,
+        } => {
+            let input = NewItem {
+                front: front.clone().unwrap_or_default(),
+                back: back.clone().unwrap_or_default(),
+                item_type: item_type.clone(),
+                context: context.clone(),
+                context_ja: context_ja.clone(),
+                source: source.clone(),
+                tags: Vec::new(),
+            };
+            let single = front.is_some() || back.is_some();
+            cmd_add(&config, &input, single, *force, *link)
+        }
+        Command::List {
+            due,
+            weak,
+            status,
+            limit,
+            fields,
+            full,
+        } => cmd_list(
+            &config,
+            *due,
+            *weak,
+            status.as_deref(),
+            *limit,
+            fields.as_deref(),
+            *full,
+        ),
+        Command::Search { query, limit } => cmd_search(&config, query, *limit),
+        Command::Show { id } => cmd_show(&config, id),
+        Command::Review {
+            id,
+            result,
+            difficulty,
+            response_ms,
+        } => cmd_review(&config, id.as_deref(), result.as_deref(), difficulty.as_deref(), *response_ms),
+        Command::Study { limit, typed, reverse } => cmd_study(&config, *limit, *typed, *reverse),
+        Command::Quiz {
+            mode,
+            n,
+            options,
+            reverse,
+            seed,
+        } => cmd_quiz(&config, mode, *n, *options as usize, *reverse, *seed),
+        Command::Cloze { id, dry_run } => cmd_cloze(&config, id.as_deref(), *dry_run),
+        Command::Session { action } => cmd_session(&config, action),
+        Command::Plan { size, mix } => cmd_plan(&config, *size, *mix),
+        Command::Check {
+            id,
+            answer,
+            reverse,
+            dry_run,
+        } => cmd_check(&config, id, answer, *reverse, *dry_run),
+        Command::Import {
+            file,
+            format,
+            columns,
+            no_header,
+            on_duplicate,
+            dry_run,
+        } => cmd_import(
+            &config,
+            file.as_deref(),
+            format,
+            columns.as_deref(),
+            !*no_header,
+            on_duplicate,
+            *dry_run,
         ),
-        Command::List { due, weak, status, limit } => {
-            cmd_list(&cli.data, *due, *weak, status.as_deref(), *limit)
+        Command::Export {
+            format,
+            output,
+            due_within,
+            status,
+            tag,
+        } => cmd_export(
+            &config,
+            format,
+            output.as_deref(),
+            *due_within,
+            status.as_deref(),
+            tag.as_deref(),
+        ),
+        Command::Merge { other, prefer, dry_run } => {
+            cmd_merge(&config, other, prefer == "theirs", *dry_run)
         }
-        Command::Review { id, result, difficulty } => {
-            cmd_review(&cli.data, id.as_deref(), result.as_deref(), difficulty.as_deref())
+        Command::Convert { dest, layout } => cmd_convert(&config, dest, layout),
+        Command::Encrypt => cmd_encrypt(&config, true),
+        Command::Decrypt => cmd_encrypt(&config, false),
+        Command::Profiles { .. } | Command::Config { .. } => unreachable!("handled above"),
+        Command::Report { format, period, output } => {
+            cmd_report(&config, format, period, output.as_deref())
         }
-        Command::Stats => cmd_stats(&cli.data),
+        Command::Stats => cmd_stats(&config),
+        Command::Forecast { days } => cmd_forecast(&config, *days as usize),
+        Command::Activity { goal, heatmap } => cmd_activity(&config, *goal, *heatmap),
+        Command::Analytics { action } => cmd_analytics(&config, action),
     }
 }
 
@@ -46,97 +158,212 @@ fn read_stdin() -> Result<String, AppError> {
     Ok(buf)
 }
 
+/// Add the item given by flags, or (without --front/--back) a batch from
+/// stdin, using the other flags as defaults for each element.
 fn cmd_add(
-    data_path: &std::path::Path,
-    front: &str,
-    back: &str,
-    item_type: Option<&str>,
-    context: Option<&str>,
-    context_ja: Option<&str>,
-    source: Option<&str>,
+    config: &Config,
+    input: &NewItem,
+    single: bool,
+    force: bool,
+    link: bool,
 ) -> Result<serde_json::Value, AppError> {
-    if front.trim().is_empty() || back.trim().is_empty() {
-        return Err(AppError::InvalidInput("'front' and 'back' are required".to_string()));
+    if single {
+        // Single-item mode
+        let mut db = storage::load(&config.data, &config.srs)?;
+        let mut response = add_item(&mut db, input, force, link, &config.srs, Utc::now())?;
+        response["total_items"] = serde_json::json!(db.items.len());
+        storage::save(&config.data, &mut db)?;
+        Ok(success_json(response))
+    } else {
+        // Batch mode: read JSON array from stdin
+        add_batch(config, input, force, link)
     }
+}
 
-    let mut db = storage::load(data_path)?;
-
-    // Duplicate check (case-insensitive on front)
-    let front_lower = front.to_lowercase();
-    if db.items.iter().any(|item| item.front.to_lowercase() == front_lower) {
-        return Err(AppError::Duplicate(front.to_string()));
+fn add_batch(config: &Config, defaults: &NewItem, force: bool, link: bool) -> Result<serde_json::Value, AppError> {
+    let input_str = read_stdin()?;
+    let inputs: Vec<serde_json::Value> = serde_json::from_str(&input_str)
+        .map_err(|e| AppError::InvalidInput(format!("Invalid JSON: {e}")))?;
+    if inputs.is_empty() {
+        return Err(AppError::InvalidInput("Empty add array".to_string()));
     }
 
+    let mut db = storage::load(&config.data, &config.srs)?;
     let now = Utc::now();
-    let id = generate_id(&now, db.items.len());
-    let resolved_type = item_type
-        .map(String::from)
-        .unwrap_or_else(|| auto_detect_type(front));
+    let mut results = Vec::new();
+    let mut counts: HashMap<&str, usize> = HashMap::new();
 
-    let next_review = sm2::next_review(&now, 1.0); // Review tomorrow
+    for (index, value) in inputs.into_iter().enumerate() {
+        let front = value.get("front").cloned().unwrap_or(serde_json::Value::Null);
+        let added = serde_json::from_value::<NewItem>(value)
+            .map_err(|e| AppError::InvalidInput(e.to_string()))
+            .and_then(|input| {
+                let input = NewItem {
+                    item_type: input.item_type.or_else(|| defaults.item_type.clone()),
+                    context: input.context.or_else(|| defaults.context.clone()),
+                    context_ja: input.context_ja.or_else(|| defaults.context_ja.clone()),
+                    source: input.source.or_else(|| defaults.source.clone()),
+                    ..input
+                };
+                add_item(&mut db, &input, force, link, &config.srs, now)
+            });
+        let (outcome, mut entry) = match added {
+            Ok(entry) => ("added", entry),
+            Err(AppError::Duplicate { existing_id, .. }) => {
+                ("duplicate", serde_json::json!({ "front": front, "existing_id": existing_id }))
+            }
+            Err(AppError::InvalidInput(reason)) => {
+                ("invalid", serde_json::json!({ "front": front, "reason": reason }))
+            }
+            Err(e) => return Err(e),
+        };
+        entry["index"] = serde_json::json!(index);
+        entry["outcome"] = serde_json::json!(outcome);
+        *counts.entry(outcome).or_insert(0) += 1;
+        results.push(entry);
+    }
 
-    let item = LearningItem {
-        id,
-        item_type: resolved_type,
-        front: front.to_string(),
-        back: back.to_string(),
-        context: context.map(String::from),
-        context_ja: context_ja.map(String::from),
-        source: source.map(String::from),
-        created_at: now,
-        last_quizzed: None,
-        next_review,
-        times_quizzed: 0,
-        times_correct: 0,
-        ease_factor: 2.5,
-        interval_days: 0.0,
-        status: "new".to_string(),
-        extra: HashMap::new(),
-    };
+    if counts.contains_key("added") {
+        storage::save(&config.data, &mut db)?;
+    }
 
-    db.items.push(item);
-    let added = db.items.last().unwrap();
-    let total = db.items.len();
+    Ok(success_json(serde_json::json!({
+        "results": results,
+        "summary": {
+            "total": results.len(),
+            "added": counts.get("added").unwrap_or(&0),
+            "duplicate": counts.get("duplicate").unwrap_or(&0),
+            "invalid": counts.get("invalid").unwrap_or(&0),
+        },
+        "total_items": db.items.len(),
+    })))
+}
 
-    let response = success_json(serde_json::json!({
-        "id": added.id,
-        "front": added.front,
-        "back": added.back,
-        "type": added.item_type,
-        "total_items": total,
-    }));
+/// Add one item to the in-memory database, linking it both ways with an item
+/// sharing its lemma when `link` is set. Returns the per-item response (without totals).
+fn add_item(
+    db: &mut models::SrsDatabase,
+    input: &NewItem,
+    force: bool,
+    link: bool,
+    params: &sm2::Params,
+    now: chrono::DateTime<Utc>,
+) -> Result<serde_json::Value, AppError> {
+    let front = input.front.as_str();
+    if front.trim().is_empty() || input.back.trim().is_empty() {
+        return Err(AppError::InvalidInput("'front' and 'back' are required".to_string()));
+    }
 
-    storage::save(data_path, &mut db)?;
+    // Duplicate check on normalized front
+    if !force {
+        if let Some(existing) = dedup::find_duplicate(&db.items, front) {
+            return Err(AppError::Duplicate {
+                front: front.to_string(),
+                existing_id: existing.id.clone(),
+            });
+        }
+    }
+
+    // Lemma check: report items sharing the lemma; with --link, also record
+    // the new item and the first one as related forms of each other
+    let related: Vec<(String, String, String)> = lemma::related_items(&db.items, front)
+        .into_iter()
+        .map(|(item, lemma)| (item.id.clone(), item.front.clone(), lemma))
+        .collect();
+    let linked = related.first().filter(|_| link).cloned();
+    let same_lemma: Vec<serde_json::Value> = related
+        .iter()
+        .map(|(id, item_front, lemma)| {
+            serde_json::json!({
+                "id": id,
+                "front": item_front,
+                "lemma": lemma,
+            })
+        })
+        .collect();
+
+    let near_duplicates: Vec<serde_json::Value> = dedup::near_duplicates(&db.items, front)
+        .into_iter()
+        .map(|(item, similarity)| {
+            serde_json::json!({
+                "id": item.id,
+                "front": item.front,
+                "similarity": (similarity * 100.0).round() / 100.0,
+            })
+        })
+        .collect();
+
+    let mut item = new_item(input, generate_id(&now, db.items.len()), now, params);
+    if let Some((_, item_front, _)) = &linked {
+        item.related_forms.push(item_front.clone());
+    }
+    let mut response = serde_json::json!({
+        "id": item.id,
+        "front": item.front,
+        "back": item.back,
+        "type": item.item_type,
+        "near_duplicates": near_duplicates,
+        "same_lemma": same_lemma,
+    });
+    db.items.push(item);
+
+    if let Some((id, _, lemma)) = linked {
+        let target = db.items.iter_mut().find(|i| i.id == id).unwrap();
+        if !target.related_forms.iter().any(|f| f == front) {
+            target.related_forms.push(front.to_string());
+        }
+        response["linked_to"] = serde_json::json!(id);
+        response["lemma"] = serde_json::json!(lemma);
+    }
 
     Ok(response)
 }
 
+/// Fields returned by `list` when neither `--fields` nor `--full` is given.
+const LIST_DEFAULT_FIELDS: &[&str] = &["id", "front", "back", "type", "context", "context_ja"];
+
+/// Field names selectable via `list --fields` besides `extra` keys.
+const ITEM_FIELDS: &[&str] = &[
+    "id",
+    "type",
+    "front",
+    "back",
+    "context",
+    "context_ja",
+    "source",
+    "created_at",
+    "last_quizzed",
+    "next_review",
+    "times_quizzed",
+    "times_correct",
+    "ease_factor",
+    "interval_days",
+    "status",
+    "tags",
+    "related_forms",
+    "reviews",
+    "cloze_of",
+    "accuracy",
+];
+
 fn cmd_list(
-    data_path: &std::path::Path,
+    config: &Config,
     due: bool,
     weak: bool,
     status_filter: Option<&str>,
     limit: Option<usize>,
+    fields: Option<&[String]>,
+    full: bool,
 ) -> Result<serde_json::Value, AppError> {
-    let db = storage::load_existing(data_path)?;
+    let db = storage::load_existing(&config.data, &config.srs)?;
     let now = Utc::now();
 
     let mut items: Vec<&LearningItem> = if due {
-        let mut due_items: Vec<_> = db.items.iter().filter(|i| i.next_review <= now).collect();
-        // Sort: overdue first (earliest next_review), then lowest ease_factor
-        due_items.sort_by(|a, b| {
-            a.next_review
-                .cmp(&b.next_review)
-                .then(a.ease_factor.partial_cmp(&b.ease_factor).unwrap())
-        });
-        due_items
+        due_items(&db.items, &now)
     } else if weak {
         db.items
             .iter()
-            .filter(|i| {
-                i.times_quizzed >= 2
-                    && i.accuracy().map_or(false, |acc| acc < 70.0)
-            })
+            .filter(|i| config.srs.is_weak(i.times_quizzed, i.accuracy()))
             .collect()
     } else if let Some(st) = status_filter {
         db.items.iter().filter(|i| i.status == st).collect()
@@ -148,17 +375,43 @@ fn cmd_list(
         items.truncate(lim);
     }
 
+    let selected: Vec<&str> = match fields {
+        Some(names) => {
+            let names: Vec<&str> = names
+                .iter()
+                .map(|n| n.trim())
+                .filter(|n| !n.is_empty())
+                .collect();
+            if names.is_empty() {
+                return Err(AppError::InvalidInput("--fields must name at least one field".to_string()));
+            }
+            for name in &names {
+                let known = ITEM_FIELDS.contains(name)
+                    || db.items.iter().any(|i| i.extra.contains_key(*name));
+                if !known {
+                    return Err(AppError::InvalidInput(format!("Unknown field '{name}'")));
+                }
+            }
+            names
+        }
+        None => LIST_DEFAULT_FIELDS.to_vec(),
+    };
+
     let items_json: Vec<serde_json::Value> = items
         .iter()
         .map(|i| {
-            serde_json::json!({
-                "id": i.id,
-                "front": i.front,
-                "back": i.back,
-                "type": i.item_type,
-                "context": i.context,
-                "context_ja": i.context_ja,
-            })
+            let map = i.to_full_json();
+            if full {
+                return serde_json::Value::Object(map);
+            }
+            let picked = selected
+                .iter()
+                .map(|&name| {
+                    let value = map.get(name).cloned().unwrap_or(serde_json::Value::Null);
+                    (name.to_string(), value)
+                })
+                .collect();
+            serde_json::Value::Object(picked)
         })
         .collect();
 
@@ -168,50 +421,118 @@ fn cmd_list(
     })))
 }
 
+/// Items due at `now`: overdue first (earliest next_review), then lowest ease_factor.
+fn due_items<'a>(items: &'a [LearningItem], now: &chrono::DateTime<Utc>) -> Vec<&'a LearningItem> {
+    let mut due: Vec<_> = items.iter().filter(|i| i.next_review <= *now).collect();
+    due.sort_by(|a, b| {
+        a.next_review
+            .cmp(&b.next_review)
+            .then(a.ease_factor.partial_cmp(&b.ease_factor).unwrap())
+    });
+    due
+}
+
+fn cmd_search(
+    config: &Config,
+    query: &str,
+    limit: Option<usize>,
+) -> Result<serde_json::Value, AppError> {
+    if query.trim().is_empty() {
+        return Err(AppError::InvalidInput("Search query is empty".to_string()));
+    }
+
+    let db = storage::load_existing(&config.data, &config.srs)?;
+    let mut hits = search::search(&db.items, query);
+    if let Some(lim) = limit {
+        hits.truncate(lim);
+    }
+
+    let results: Vec<serde_json::Value> = hits
+        .iter()
+        .map(|hit| {
+            serde_json::json!({
+                "id": hit.item.id,
+                "front": hit.item.front,
+                "back": hit.item.back,
+                "type": hit.item.item_type,
+                "score": (hit.score * 1000.0).round() / 1000.0,
+                "matched_field": hit.field,
+            })
+        })
+        .collect();
+
+    Ok(success_json(serde_json::json!({
+        "query": query,
+        "count": results.len(),
+        "results": results,
+    })))
+}
+
+fn cmd_show(config: &Config, id_prefix: &str) -> Result<serde_json::Value, AppError> {
+    let db = storage::load_existing(&config.data, &config.srs)?;
+    let now = Utc::now();
+    let item = &db.items[find_index_by_prefix(&db.items, id_prefix)?];
+
+    let overdue_secs = (now - item.next_review).num_seconds().max(0);
+    let days_overdue = (overdue_secs as f64 / 86400.0 * 10.0).round() / 10.0;
+    let recall = sm2::recall_probability(item.interval_days, item.last_quizzed.as_ref(), &now)
+        .map(|p| (p * 1000.0).round() / 1000.0);
+
+    Ok(success_json(serde_json::json!({
+        "item": item.to_full_json(),
+        "due": item.next_review <= now,
+        "days_overdue": days_overdue,
+        "recall_probability": recall,
+    })))
+}
+
 fn cmd_review(
-    data_path: &std::path::Path,
+    config: &Config,
     id: Option<&str>,
     result: Option<&str>,
     difficulty: Option<&str>,
+    response_ms: Option<u64>,
 ) -> Result<serde_json::Value, AppError> {
     if let (Some(item_id), Some(res)) = (id, result) {
         // Single-item mode
-        review_single(data_path, item_id, res, difficulty)
+        review_single(config, item_id, res, difficulty, response_ms)
     } else {
         // Batch mode: read JSON array from stdin
-        review_batch(data_path)
+        review_batch(config)
     }
 }
 
 fn review_single(
-    data_path: &std::path::Path,
+    config: &Config,
     id_prefix: &str,
     result: &str,
     difficulty: Option<&str>,
+    response_ms: Option<u64>,
 ) -> Result<serde_json::Value, AppError> {
     let inputs = vec![ReviewInput {
         id: id_prefix.to_string(),
         result: result.to_string(),
         difficulty: difficulty.map(String::from),
+        response_ms,
     }];
-    review_items(data_path, &inputs)
+    review_items(config, &inputs)
 }
 
-fn review_batch(data_path: &std::path::Path) -> Result<serde_json::Value, AppError> {
+fn review_batch(config: &Config) -> Result<serde_json::Value, AppError> {
     let input_str = read_stdin()?;
     let inputs: Vec<ReviewInput> = serde_json::from_str(&input_str)
         .map_err(|e| AppError::InvalidInput(format!("Invalid JSON: {e}")))?;
     if inputs.is_empty() {
         return Err(AppError::InvalidInput("Empty review array".to_string()));
     }
-    review_items(data_path, &inputs)
+    review_items(config, &inputs)
 }
 
 fn review_items(
-    data_path: &std::path::Path,
+    config: &Config,
     inputs: &[ReviewInput],
 ) -> Result<serde_json::Value, AppError> {
-    let mut db = storage::load_existing(data_path)?;
+    let mut db = storage::load_existing(&config.data, &config.srs)?;
     let now = Utc::now();
     let mut results = Vec::new();
     let mut total_correct = 0u32;
@@ -219,44 +540,24 @@ fn review_items(
 
     for input in inputs {
         let item = find_item_by_prefix(&mut db.items, &input.id)?;
-
-        let is_correct = input.result == "correct";
-        let difficulty = input.difficulty.as_deref().unwrap_or("good");
-
-        let (new_interval, new_ease) = if is_correct {
+        if input.result == "correct" {
             total_correct += 1;
-            sm2::review_correct(item.times_quizzed, item.interval_days, item.ease_factor, difficulty)
         } else {
             total_incorrect += 1;
-            sm2::review_incorrect(item.ease_factor)
-        };
-
-        item.interval_days = new_interval;
-        item.ease_factor = new_ease;
-        item.times_quizzed += 1;
-        if is_correct {
-            item.times_correct += 1;
         }
-        item.last_quizzed = Some(now);
-        item.next_review = sm2::next_review(&now, new_interval);
-        item.status = sm2::compute_status(
-            item.interval_days,
-            item.accuracy(),
-            item.times_quizzed,
-            &now,
-            &item.next_review,
-        );
+        apply_review(item, &input.result, input.difficulty.as_deref(), input.response_ms, &config.srs, now);
 
         results.push(serde_json::json!({
             "id": item.id,
             "result": input.result,
+            "difficulty": item.reviews.last().and_then(|r| r.difficulty.clone()),
             "next_review": item.next_review.to_rfc3339(),
             "interval_days": item.interval_days,
             "status": item.status,
         }));
     }
 
-    storage::save(data_path, &mut db)?;
+    storage::save(&config.data, &mut db)?;
 
     Ok(success_json(serde_json::json!({
         "results": results,
@@ -268,8 +569,1030 @@ fn review_items(
     })))
 }
 
-fn cmd_stats(data_path: &std::path::Path) -> Result<serde_json::Value, AppError> {
-    let db = storage::load_existing(data_path)?;
+/// Apply one review to an item: SM-2 scheduling, counters, review log and status.
+/// A slow "good" answer is downgraded per `params.slow_answer_ms`.
+fn apply_review(
+    item: &mut LearningItem,
+    result: &str,
+    difficulty: Option<&str>,
+    response_ms: Option<u64>,
+    params: &sm2::Params,
+    now: chrono::DateTime<Utc>,
+) {
+    let is_correct = result == "correct";
+    let difficulty = params.effective_difficulty(difficulty.unwrap_or("good"), response_ms);
+
+    let (new_interval, new_ease) = if is_correct {
+        sm2::review_correct(params, item.times_quizzed, item.interval_days, item.ease_factor, difficulty)
+    } else {
+        sm2::review_incorrect(params, item.ease_factor)
+    };
+
+    item.interval_days = new_interval;
+    item.ease_factor = new_ease;
+    item.times_quizzed += 1;
+    if is_correct {
+        item.times_correct += 1;
+    }
+    item.last_quizzed = Some(now);
+    item.next_review = sm2::next_review(&now, new_interval);
+    item.reviews.push(ReviewRecord {
+        at: now,
+        result: result.to_string(),
+        difficulty: is_correct.then(|| difficulty.to_string()),
+        interval_days: new_interval,
+        ease_factor: new_ease,
+        response_ms,
+    });
+    item.status = sm2::compute_status(
+        params,
+        item.interval_days,
+        item.accuracy(),
+        item.times_quizzed,
+        &now,
+        &item.next_review,
+    );
+}
+
+fn cmd_study(
+    config: &Config,
+    limit: Option<usize>,
+    typed: bool,
+    reverse: bool,
+) -> Result<serde_json::Value, AppError> {
+    use std::io::IsTerminal;
+    use study::{Card, Grade, StudyUi};
+
+    let mut db = storage::load_existing(&config.data, &config.srs)?;
+    let started = Utc::now();
+    let mut ids: Vec<String> = due_items(&db.items, &started).into_iter().map(|i| i.id.clone()).collect();
+    let due_total = ids.len();
+    ids.truncate(limit.unwrap_or(usize::MAX));
+
+    let mut ui: Box<dyn StudyUi> = if std::io::stdin().is_terminal() && std::io::stdout().is_terminal() {
+        Box::new(study::TerminalUi::new()?)
+    } else {
+        Box::new(study::LineUi::new(std::io::stdin().lock(), std::io::stderr()))
+    };
+
+    let mut results = Vec::new();
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for (n, id) in ids.iter().enumerate() {
+        let idx = db.items.iter().position(|i| &i.id == id).unwrap();
+        let (n, total) = (n + 1, ids.len());
+        let card = Card::new(&db.items[idx], reverse);
+        let shown = std::time::Instant::now();
+        let (grade, checked, elapsed) = if typed {
+            let Some(answer) = ui.type_answer(&card, n, total)? else {
+                break;
+            };
+            let elapsed = shown.elapsed();
+            let checked = answer::check(card.answer, &answer);
+            (checked.verdict.grade(), Some(checked), elapsed)
+        } else {
+            if !ui.show_front(&card, n, total)? {
+                break;
+            }
+            // Time to recall, not to grade
+            let recalled = shown.elapsed();
+            let Some(grade) = ui.reveal(&card, n, total)? else {
+                break;
+            };
+            (grade, None, recalled)
+        };
+        let response_ms = Some(elapsed.as_millis() as u64);
+
+        let item = &mut db.items[idx];
+        apply_review(item, grade.result(), grade.difficulty(), response_ms, &config.srs, Utc::now());
+        *counts.entry(grade.name()).or_default() += 1;
+        let mut result = serde_json::json!({
+            "id": item.id,
+            "front": item.front,
+            "grade": grade.name(),
+            "next_review": item.next_review.to_rfc3339(),
+            "status": item.status,
+        });
+        if let Some(checked) = &checked {
+            result["verdict"] = serde_json::json!(checked.verdict.name());
+        }
+        results.push(result);
+        storage::save(&config.data, &mut db)?;
+
+        if let Some(checked) = &checked {
+            let card = Card::new(&db.items[idx], reverse);
+            if !ui.show_checked(&card, n, total, checked, grade)? {
+                break;
+            }
+        }
+    }
+    // Restore the terminal before the summary is printed
+    drop(ui);
+
+    let reviewed = results.len();
+    let correct = reviewed - counts.get("again").copied().unwrap_or(0);
+    let accuracy = (reviewed > 0).then(|| (correct as f64 / reviewed as f64 * 1000.0).round() / 10.0);
+    let grades: serde_json::Map<String, serde_json::Value> = Grade::ALL
+        .iter()
+        .map(|g| (g.name().to_string(), serde_json::json!(counts.get(g.name()).copied().unwrap_or(0))))
+        .collect();
+
+    Ok(success_json(serde_json::json!({
+        "reviewed": reviewed,
+        "completed": reviewed == ids.len(),
+        "remaining_due": due_total - reviewed,
+        "grades": grades,
+        "accuracy": accuracy,
+        "duration_secs": (Utc::now() - started).num_seconds(),
+        "results": results,
+    })))
+}
+
+fn cmd_quiz(
+    config: &Config,
+    mode: &str,
+    n: usize,
+    options: usize,
+    reverse: bool,
+    seed: Option<u64>,
+) -> Result<serde_json::Value, AppError> {
+    let db = storage::load_existing(&config.data, &config.srs)?;
+    let now = Utc::now();
+    let seed = seed.unwrap_or_else(|| now.timestamp_nanos_opt().unwrap_or_default() as u64);
+    let mut rng = quiz::Rng::new(seed);
+
+    let due = due_items(&db.items, &now);
+    let questions: Vec<serde_json::Value> = quiz::choice_questions(&due, &db.items, n, options, reverse, &mut rng)
+        .into_iter()
+        .map(|q| {
+            serde_json::json!({
+                "id": q.item.id,
+                "type": q.item.item_type,
+                "question": q.prompt,
+                "options": q.options,
+                "answer_index": q.answer_index,
+            })
+        })
+        .collect();
+
+    Ok(success_json(serde_json::json!({
+        "mode": mode,
+        "reverse": reverse,
+        "seed": seed,
+        "count": questions.len(),
+        "due": due.len(),
+        "questions": questions,
+    })))
+}
+
+fn cmd_cloze(config: &Config, id_prefix: Option<&str>, dry_run: bool) -> Result<serde_json::Value, AppError> {
+    let mut db = storage::load_existing(&config.data, &config.srs)?;
+    let now = Utc::now();
+    let parents: Vec<usize> = match id_prefix {
+        Some(prefix) => vec![find_index_by_prefix(&db.items, prefix)?],
+        None => (0..db.items.len())
+            .filter(|&i| db.items[i].cloze_of.is_none() && db.items[i].context.is_some())
+            .collect(),
+    };
+
+    let mut results = Vec::new();
+    let mut new_items = Vec::new();
+    let (mut created, mut existing, mut no_match) = (0, 0, 0);
+    for idx in parents {
+        let parent = &db.items[idx];
+        let clozes = match (&parent.context, &parent.cloze_of) {
+            (_, Some(_)) => {
+                return Err(AppError::InvalidInput(format!("{} is already a cloze card", parent.id)));
+            }
+            (Some(context), None) => cloze::clozes(context, &parent.front),
+            (None, None) => Vec::new(),
+        };
+        if clozes.is_empty() {
+            no_match += 1;
+            results.push(serde_json::json!({
+                "item_id": parent.id,
+                "front": parent.front,
+                "outcome": if parent.context.is_some() { "no_match" } else { "no_context" },
+            }));
+            continue;
+        }
+        for c in clozes {
+            let found = db
+                .items
+                .iter()
+                .chain(&new_items)
+                .find(|i: &&LearningItem| i.cloze_of.as_deref() == Some(parent.id.as_str()) && i.front == c.text);
+            let (outcome, id) = match found {
+                Some(card) => {
+                    existing += 1;
+                    ("exists", card.id.clone())
+                }
+                None => {
+                    created += 1;
+                    let input = NewItem {
+                        front: c.text.clone(),
+                        back: c.answer.clone(),
+                        item_type: Some("cloze".to_string()),
+                        context: None,
+                        context_ja: parent.context_ja.clone(),
+                        source: parent.source.clone(),
+                        tags: parent.tags.clone(),
+                    };
+                    let id = generate_id(&now, db.items.len() + new_items.len());
+                    let mut item = new_item(&input, id.clone(), now, &config.srs);
+                    item.cloze_of = Some(parent.id.clone());
+                    new_items.push(item);
+                    ("created", id)
+                }
+            };
+            results.push(serde_json::json!({
+                "item_id": parent.id,
+                "front": parent.front,
+                "outcome": outcome,
+                "id": id,
+                "text": c.text,
+                "answer": c.answer,
+            }));
+        }
+    }
+
+    if !dry_run && !new_items.is_empty() {
+        db.items.extend(new_items);
+        storage::save(&config.data, &mut db)?;
+    }
+    Ok(success_json(serde_json::json!({
+        "dry_run": dry_run,
+        "created": created,
+        "existing": existing,
+        "no_match": no_match,
+        "results": results,
+    })))
+}
+
+fn cmd_plan(config: &Config, size: usize, mix: planner::Mix) -> Result<serde_json::Value, AppError> {
+    let db = storage::load_existing(&config.data, &config.srs)?;
+    let planned = planner::plan(&db.items, size, mix, &config.srs, &Utc::now());
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    let items: Vec<serde_json::Value> = planned
+        .iter()
+        .map(|p| {
+            *counts.entry(p.kind.name()).or_default() += 1;
+            serde_json::json!({
+                "id": p.item.id,
+                "type": p.item.item_type,
+                "front": p.item.front,
+                "back": p.item.back,
+                "pool": p.kind.name(),
+            })
+        })
+        .collect();
+    Ok(success_json(serde_json::json!({
+        "count": items.len(),
+        "mix": format!("{}:{}:{}", mix.new, mix.review, mix.weak),
+        "pools": {
+            "new": counts.get("new").copied().unwrap_or(0),
+            "review": counts.get("review").copied().unwrap_or(0),
+            "weak": counts.get("weak").copied().unwrap_or(0),
+        },
+        "items": items,
+    })))
+}
+
+fn cmd_session(config: &Config, action: &cli::SessionAction) -> Result<serde_json::Value, AppError> {
+    use cli::SessionAction;
+    let path = session::path(&config.data);
+    let encrypted = storage::Layout::of(&config.data) == storage::Layout::Encrypted;
+    let no_session = || AppError::InvalidInput("No active session; start one with `session start`".to_string());
+
+    match action {
+        SessionAction::Start { n, reverse, mix } => {
+            if let Some(active) = session::load(&path)? {
+                return Err(AppError::InvalidInput(format!(
+                    "A session started at {} is in progress ({}/{} answered); finish it first",
+                    active.started_at.to_rfc3339(),
+                    active.answered_count(),
+                    active.questions.len()
+                )));
+            }
+            let db = storage::load_existing(&config.data, &config.srs)?;
+            let now = Utc::now();
+            let due = due_items(&db.items, &now);
+            let targets: Vec<&LearningItem> = match mix {
+                Some(mix) => planner::plan(&db.items, *n, *mix, &config.srs, &now)
+                    .into_iter()
+                    .map(|p| p.item)
+                    .collect(),
+                None => due.iter().take(*n).copied().collect(),
+            };
+            let active = session::Session::new(&targets, *reverse, now);
+            if !active.questions.is_empty() {
+                session::save(&path, &active, encrypted)?;
+            }
+            Ok(success_json(serde_json::json!({
+                "session": path.display().to_string(),
+                "started_at": active.started_at.to_rfc3339(),
+                "count": active.questions.len(),
+                "due": due.len(),
+                "questions": session_questions(&active, false),
+            })))
+        }
+        SessionAction::Answer { n, grade, response_ms } => {
+            let grade = study::Grade::parse(grade)
+                .ok_or_else(|| AppError::InvalidInput(format!("Invalid grade '{grade}': use again, hard, good or easy")))?;
+            let mut active = session::load(&path)?.ok_or_else(no_session)?;
+            let mut db = storage::load_existing(&config.data, &config.srs)?;
+            let question = active.question_mut(*n)?;
+            if let Some(previous) = &question.grade {
+                return Err(AppError::InvalidInput(format!("Question {n} was already answered ({previous})")));
+            }
+            let idx = db
+                .items
+                .iter()
+                .position(|i| i.id == question.id)
+                .ok_or_else(|| AppError::NotFound(question.id.clone()))?;
+
+            // The session file is written first: if saving the data file then
+            // fails, resume/finish find the answer unapplied and record it.
+            let now = Utc::now();
+            question.grade = Some(grade.name().to_string());
+            question.answered_at = Some(now);
+            question.response_ms = *response_ms;
+            let id = question.id.clone();
+            session::save(&path, &active, encrypted)?;
+            let item = &mut db.items[idx];
+            apply_review(item, grade.result(), grade.difficulty(), *response_ms, &config.srs, now);
+            let response = serde_json::json!({
+                "n": n,
+                "id": id,
+                "grade": grade.name(),
+                "next_review": item.next_review.to_rfc3339(),
+                "status": item.status,
+                "answered": active.answered_count(),
+                "remaining": active.questions.len() - active.answered_count(),
+            });
+            storage::save(&config.data, &mut db)?;
+            Ok(success_json(response))
+        }
+        SessionAction::Resume => {
+            let active = session::load(&path)?.ok_or_else(no_session)?;
+            let recovered = apply_session_answers(config, &active)?;
+            Ok(success_json(serde_json::json!({
+                "started_at": active.started_at.to_rfc3339(),
+                "reverse": active.reverse,
+                "count": active.questions.len(),
+                "answered": active.answered_count(),
+                "recovered": recovered,
+                "questions": session_questions(&active, true),
+            })))
+        }
+        SessionAction::Finish => {
+            let active = session::load(&path)?.ok_or_else(no_session)?;
+            let recovered = apply_session_answers(config, &active)?;
+            std::fs::remove_file(&path)?;
+
+            let mut counts: HashMap<&str, usize> = HashMap::new();
+            for (grade, _) in active.questions.iter().filter_map(|q| q.answered()) {
+                *counts.entry(grade.name()).or_default() += 1;
+            }
+            let reviewed = active.answered_count();
+            let correct = reviewed - counts.get("again").copied().unwrap_or(0);
+            let accuracy = (reviewed > 0).then(|| (correct as f64 / reviewed as f64 * 1000.0).round() / 10.0);
+            let grades: serde_json::Map<String, serde_json::Value> = study::Grade::ALL
+                .iter()
+                .map(|g| (g.name().to_string(), serde_json::json!(counts.get(g.name()).copied().unwrap_or(0))))
+                .collect();
+            let results: Vec<serde_json::Value> = active
+                .questions
+                .iter()
+                .enumerate()
+                .map(|(idx, q)| serde_json::json!({ "n": idx + 1, "id": q.id, "grade": q.grade }))
+                .collect();
+            Ok(success_json(serde_json::json!({
+                "started_at": active.started_at.to_rfc3339(),
+                "reviewed": reviewed,
+                "unanswered": active.questions.len() - reviewed,
+                "recovered": recovered,
+                "grades": grades,
+                "accuracy": accuracy,
+                "results": results,
+            })))
+        }
+    }
+}
+
+/// Session questions as JSON, numbered from 1; only unanswered ones when
+/// `unanswered_only`.
+fn session_questions(active: &session::Session, unanswered_only: bool) -> Vec<serde_json::Value> {
+    active
+        .questions
+        .iter()
+        .enumerate()
+        .filter(|(_, q)| !unanswered_only || q.grade.is_none())
+        .map(|(idx, q)| {
+            let mut json = serde_json::json!({
+                "n": idx + 1,
+                "id": q.id,
+                "type": q.item_type,
+                "question": q.prompt,
+                "answer": q.answer,
+            });
+            if let Some(hint) = &q.hint {
+                json["hint"] = serde_json::json!(hint);
+            }
+            json
+        })
+        .collect()
+}
+
+/// Record answers saved in the session file but missing from the data file
+/// (an interrupted `session answer`). Returns how many were recorded.
+fn apply_session_answers(config: &Config, active: &session::Session) -> Result<usize, AppError> {
+    let mut db = storage::load_existing(&config.data, &config.srs)?;
+    let mut recovered = 0;
+    for question in &active.questions {
+        let Some((grade, at)) = question.answered() else { continue };
+        let Some(item) = db.items.iter_mut().find(|i| i.id == question.id) else { continue };
+        if !question.is_applied(item) {
+            apply_review(item, grade.result(), grade.difficulty(), question.response_ms, &config.srs, at);
+            recovered += 1;
+        }
+    }
+    if recovered > 0 {
+        storage::save(&config.data, &mut db)?;
+    }
+    Ok(recovered)
+}
+
+fn cmd_check(
+    config: &Config,
+    id_prefix: &str,
+    typed: &str,
+    reverse: bool,
+    dry_run: bool,
+) -> Result<serde_json::Value, AppError> {
+    let mut db = storage::load_existing(&config.data, &config.srs)?;
+    let item = find_item_by_prefix(&mut db.items, id_prefix)?;
+    let expected = study::Card::new(item, reverse).answer.to_string();
+    let checked = answer::check(&expected, typed);
+    let grade = checked.verdict.grade();
+
+    let mut response = serde_json::json!({
+        "id": item.id,
+        "answer": typed,
+        "expected": expected,
+        "matched": checked.matched,
+        "similarity": (checked.similarity * 100.0).round() / 100.0,
+        "verdict": checked.verdict.name(),
+        "grade": grade.name(),
+        "recorded": !dry_run,
+    });
+    if !dry_run {
+        apply_review(item, grade.result(), grade.difficulty(), None, &config.srs, Utc::now());
+        response["next_review"] = serde_json::json!(item.next_review.to_rfc3339());
+        response["status"] = serde_json::json!(item.status);
+        storage::save(&config.data, &mut db)?;
+    }
+    Ok(success_json(response))
+}
+
+fn cmd_import(
+    config: &Config,
+    file: Option<&std::path::Path>,
+    format: &str,
+    columns: Option<&[String]>,
+    has_header: bool,
+    on_duplicate: &str,
+    dry_run: bool,
+) -> Result<serde_json::Value, AppError> {
+    let rows: Vec<ImportRow> = if format == "apkg" {
+        let path = file.ok_or_else(|| AppError::InvalidInput("apkg import requires a FILE".to_string()))?;
+        anki::read_package(path, &config.srs)?
+            .into_iter()
+            .enumerate()
+            .map(|(idx, note)| ImportRow {
+                line: idx + 1,
+                input: if note.input.front.is_empty() || note.input.back.is_empty() {
+                    Err("'front' and 'back' are required".to_string())
+                } else {
+                    Ok(note.input)
+                },
+                created_at: Some(note.created_at),
+                schedule: note.schedule,
+            })
+            .collect()
+    } else {
+        let delimiter = table::delimiter(format)?;
+        let content = match file {
+            Some(path) => std::fs::read_to_string(path)?,
+            None => read_stdin()?,
+        };
+        table::read_rows(&content, delimiter, columns, has_header)?
+            .into_iter()
+            .map(|(line, input)| ImportRow {
+                line,
+                input,
+                created_at: None,
+                schedule: None,
+            })
+            .collect()
+    };
+
+    let mut db = storage::load(&config.data, &config.srs)?;
+    let now = Utc::now();
+    let mut report = Vec::new();
+    let (mut added, mut updated, mut skipped, mut invalid) = (0usize, 0usize, 0usize, 0usize);
+
+    for row in rows {
+        let line = row.line;
+        let input = match row.input {
+            Ok(input) => input,
+            Err(reason) => {
+                invalid += 1;
+                report.push(serde_json::json!({ "line": line, "outcome": "invalid", "reason": reason }));
+                continue;
+            }
+        };
+
+        let existing_id = dedup::find_duplicate(&db.items, &input.front).map(|i| i.id.clone());
+        let (outcome, id) = match existing_id {
+            None => {
+                let mut item = new_item(&input, generate_id(&now, db.items.len()), now, &config.srs);
+                if let Some(created_at) = row.created_at {
+                    item.created_at = created_at;
+                }
+                if let Some(schedule) = row.schedule {
+                    apply_schedule(&mut item, schedule, &config.srs, &now);
+                }
+                let id = item.id.clone();
+                db.items.push(item);
+                added += 1;
+                ("added", id)
+            }
+            Some(id) => match on_duplicate {
+                "update" => {
+                    let item = db.items.iter_mut().find(|i| i.id == id).unwrap();
+                    update_item(item, &input);
+                    updated += 1;
+                    ("updated", id)
+                }
+                "fail" => {
+                    return Err(AppError::Duplicate {
+                        front: input.front,
+                        existing_id: id,
+                    })
+                }
+                _ => {
+                    skipped += 1;
+                    ("skipped", id)
+                }
+            },
+        };
+        report.push(serde_json::json!({
+            "line": line,
+            "front": input.front,
+            "outcome": outcome,
+            "id": id,
+        }));
+    }
+
+    let total = db.items.len();
+    if !dry_run && added + updated > 0 {
+        storage::save(&config.data, &mut db)?;
+    }
+
+    Ok(success_json(serde_json::json!({
+        "dry_run": dry_run,
+        "summary": {
+            "added": added,
+            "updated": updated,
+            "skipped": skipped,
+            "invalid": invalid,
+        },
+        "rows": report,
+        "total_items": total,
+    })))
+}
+
+/// One record to import: its position in the input (line, or note number for
+/// apkg), the item fields or why the record is invalid, and any state carried
+/// over from Anki.
+struct ImportRow {
+    line: usize,
+    input: Result<NewItem, String>,
+    created_at: Option<chrono::DateTime<Utc>>,
+    schedule: Option<anki::Schedule>,
+}
+
+fn apply_schedule(
+    item: &mut LearningItem,
+    schedule: anki::Schedule,
+    params: &sm2::Params,
+    now: &chrono::DateTime<Utc>,
+) {
+    item.last_quizzed = schedule.last_quizzed;
+    item.next_review = schedule.next_review;
+    item.times_quizzed = schedule.times_quizzed;
+    item.times_correct = schedule.times_correct;
+    item.ease_factor = schedule.ease_factor;
+    item.interval_days = schedule.interval_days;
+    item.reviews = schedule.reviews;
+    item.status = sm2::compute_status(
+        params,
+        item.interval_days,
+        item.accuracy(),
+        item.times_quizzed,
+        now,
+        &item.next_review,
+    );
+}
+
+/// Overwrite an existing item's content with the non-empty fields of `input`.
+/// Scheduling state is left untouched.
+fn update_item(item: &mut LearningItem, input: &NewItem) {
+    item.back = input.back.clone();
+    if let Some(t) = &input.item_type {
+        item.item_type = t.clone();
+    }
+    if input.context.is_some() {
+        item.context = input.context.clone();
+    }
+    if input.context_ja.is_some() {
+        item.context_ja = input.context_ja.clone();
+    }
+    if input.source.is_some() {
+        item.source = input.source.clone();
+    }
+    if !input.tags.is_empty() {
+        item.tags = input.tags.clone();
+    }
+}
+
+fn cmd_export(
+    config: &Config,
+    format: &str,
+    output: Option<&std::path::Path>,
+    due_within: Option<f64>,
+    status_filter: Option<&str>,
+    tag: Option<&str>,
+) -> Result<serde_json::Value, AppError> {
+    let db = storage::load_existing(&config.data, &config.srs)?;
+    let due_by = due_within.map(|days| sm2::next_review(&Utc::now(), days));
+    let items: Vec<&LearningItem> = db
+        .items
+        .iter()
+        .filter(|i| due_by.is_none_or(|by| i.next_review <= by))
+        .filter(|i| status_filter.is_none_or(|st| i.status == st))
+        .filter(|i| tag.is_none_or(|t| i.tags.iter().any(|it| it == t)))
+        .collect();
+
+    if format == "apkg" {
+        let path = output.ok_or_else(|| AppError::InvalidInput("apkg export requires --output".to_string()))?;
+        anki::write_package(&items, path, Utc::now())?;
+        return Ok(success_json(serde_json::json!({
+            "format": format,
+            "count": items.len(),
+            "path": path.display().to_string(),
+        })));
+    }
+
+    let content = match format {
+        "print" => print::render(&items),
+        _ => table::write_items(&items, table::delimiter(format)?)?,
+    };
+    match output {
+        Some(path) => {
+            std::fs::write(path, content)?;
+            Ok(success_json(serde_json::json!({
+                "format": format,
+                "count": items.len(),
+                "path": path.display().to_string(),
+            })))
+        }
+        None => Ok(success_json(serde_json::json!({
+            "format": format,
+            "count": items.len(),
+            "content": content,
+        }))),
+    }
+}
+
+fn cmd_merge(
+    config: &Config,
+    other_path: &std::path::Path,
+    prefer_theirs: bool,
+    dry_run: bool,
+) -> Result<serde_json::Value, AppError> {
+    let mut db = storage::load(&config.data, &config.srs)?;
+    let theirs = storage::load_existing(other_path, &config.srs)?;
+    let report = merge::merge(&mut db, theirs, prefer_theirs, &Utc::now());
+
+    let count = |outcome: &str| report.outcomes.iter().filter(|o| o.outcome == outcome).count();
+    let (added, merged) = (count("added"), count("merged"));
+    if !dry_run && added + merged > 0 {
+        storage::save(&config.data, &mut db)?;
+    }
+
+    let items: Vec<serde_json::Value> = report
+        .outcomes
+        .iter()
+        .filter(|o| o.outcome != "unchanged")
+        .map(|o| serde_json::json!({ "id": o.id, "front": o.front, "outcome": o.outcome }))
+        .collect();
+    let conflicts: Vec<serde_json::Value> = report
+        .conflicts
+        .iter()
+        .map(|c| {
+            serde_json::json!({
+                "id": c.id,
+                "front": c.front,
+                "field": c.field,
+                "ours": c.ours,
+                "theirs": c.theirs,
+            })
+        })
+        .collect();
+
+    Ok(success_json(serde_json::json!({
+        "dry_run": dry_run,
+        "summary": {
+            "added": added,
+            "merged": merged,
+            "unchanged": count("unchanged"),
+            "conflicts": conflicts.len(),
+        },
+        "items": items,
+        "conflicts": conflicts,
+        "total_items": db.items.len(),
+    })))
+}
+
+fn cmd_convert(
+    config: &Config,
+    dest: &std::path::Path,
+    layout: &str,
+) -> Result<serde_json::Value, AppError> {
+    if dest.exists() {
+        return Err(AppError::InvalidInput(format!("Destination already exists: {}", dest.display())));
+    }
+    let mut db = storage::load_existing(&config.data, &config.srs)?;
+    let layout = match layout {
+        "dir" => storage::Layout::Dir,
+        "encrypted" => storage::Layout::Encrypted,
+        _ => storage::Layout::File,
+    };
+    storage::save_as(dest, &mut db, layout)?;
+
+    Ok(success_json(serde_json::json!({
+        "from": config.data.display().to_string(),
+        "from_layout": storage::Layout::of(&config.data).name(),
+        "to": dest.display().to_string(),
+        "layout": layout.name(),
+        "count": db.items.len(),
+    })))
+}
+
+fn cmd_encrypt(config: &Config, encrypt: bool) -> Result<serde_json::Value, AppError> {
+    use storage::Layout;
+    let layout = Layout::of(&config.data);
+    match (layout, encrypt) {
+        (Layout::Dir, _) => {
+            return Err(AppError::InvalidInput(
+                "Encryption is only supported for the single-file layout".to_string(),
+            ))
+        }
+        (Layout::Encrypted, true) => {
+            return Err(AppError::InvalidInput("Data file is already encrypted".to_string()))
+        }
+        (Layout::File, false) => {
+            return Err(AppError::InvalidInput("Data file is not encrypted".to_string()))
+        }
+        _ => {}
+    }
+
+    let mut db = storage::load_existing(&config.data, &config.srs)?;
+    let target = if encrypt { Layout::Encrypted } else { Layout::File };
+    storage::save_as(&config.data, &mut db, target)?;
+
+    // An active session follows the data file
+    let session_path = session::path(&config.data);
+    if let Some(active) = session::load(&session_path)? {
+        session::save(&session_path, &active, encrypt)?;
+    }
+
+    // The backup holds the previous (plaintext) contents
+    let backup = config.data.with_extension("json.bak");
+    let removed_backup = encrypt && backup.exists();
+    if removed_backup {
+        std::fs::remove_file(&backup)?;
+    }
+
+    Ok(success_json(serde_json::json!({
+        "path": config.data.display().to_string(),
+        "layout": target.name(),
+        "count": db.items.len(),
+        "removed_backup": removed_backup,
+    })))
+}
+
+fn cmd_profiles(
+    action: &cli::ProfilesAction,
+    data: Option<&std::path::Path>,
+    active: Option<&str>,
+) -> Result<serde_json::Value, AppError> {
+    use cli::ProfilesAction;
+    let path = config::config_path();
+    let mut config = config::ConfigFile::load(&path)?;
+
+    match action {
+        ProfilesAction::List => {
+            let profiles: Vec<serde_json::Value> = config::profiles()?
+                .into_iter()
+                .map(|(name, data, origin)| {
+                    serde_json::json!({
+                        "name": name,
+                        "data": data.display().to_string(),
+                        "exists": data.exists(),
+                        "active": active == Some(name.as_str()),
+                        "origin": origin,
+                    })
+                })
+                .collect();
+            Ok(success_json(serde_json::json!({
+                "config": path.display().to_string(),
+                "count": profiles.len(),
+                "profiles": profiles,
+            })))
+        }
+        ProfilesAction::Create { name } => {
+            config::validate_profile_name(name)?;
+            if config::profiles()?.iter().any(|(n, _, _)| n == name) {
+                return Err(AppError::InvalidInput(format!("Profile '{name}' already exists")));
+            }
+            let data = data.map_or_else(|| format!("data/profiles/{name}.json"), |d| d.display().to_string());
+            config.add_profile(name, &data);
+            config.save()?;
+            Ok(success_json(serde_json::json!({
+                "created": name,
+                "data": config.profile(name).map(|p| p.display().to_string()),
+                "config": path.display().to_string(),
+            })))
+        }
+        ProfilesAction::Delete { name, purge } => {
+            let data = match config.profile(name) {
+                Some(data) => data,
+                None if config::profiles()?.iter().any(|(n, _, _)| n == name) => {
+                    return Err(AppError::InvalidInput(format!(
+                        "Profile '{name}' is defined in the user config; remove it there"
+                    )))
+                }
+                None => return Err(AppError::InvalidInput(format!("Unknown profile '{name}'"))),
+            };
+            config.remove_profile(name);
+            config.save()?;
+
+            let mut purged = Vec::new();
+            if *purge {
+                for file in [data.clone(), data.with_extension("json.bak")] {
+                    if file.is_dir() {
+                        std::fs::remove_dir_all(&file)?;
+                    } else if file.exists() {
+                        std::fs::remove_file(&file)?;
+                    } else {
+                        continue;
+                    }
+                    purged.push(file.display().to_string());
+                }
+            }
+            Ok(success_json(serde_json::json!({
+                "deleted": name,
+                "data": data.display().to_string(),
+                "purged": purged,
+            })))
+        }
+    }
+}
+
+fn cmd_config(
+    config: &Config,
+    action: &cli::ConfigAction,
+    data_flag: Option<&std::path::Path>,
+    profile: Option<&str>,
+) -> Result<serde_json::Value, AppError> {
+    use cli::ConfigAction;
+    let values = config.to_flat_json();
+
+    match action {
+        ConfigAction::Show => {
+            let files = |path: Option<std::path::PathBuf>| {
+                serde_json::json!({
+                    "path": path.as_ref().map(|p| p.display().to_string()),
+                    "exists": path.is_some_and(|p| p.exists()),
+                })
+            };
+            Ok(success_json(serde_json::json!({
+                "config": config,
+                "origins": config.origins,
+                "files": {
+                    "user": files(config::user_config_path()),
+                    "project": files(Some(config::config_path())),
+                },
+            })))
+        }
+        ConfigAction::Get { key } => {
+            let value = values
+                .get(key)
+                .ok_or_else(|| AppError::InvalidInput(format!("Unknown config key '{key}'")))?;
+            Ok(success_json(serde_json::json!({
+                "key": key,
+                "value": value,
+                "origin": config.origins.get(key),
+            })))
+        }
+        ConfigAction::Set { key, value, user } => {
+            let current = values
+                .get(key)
+                .ok_or_else(|| AppError::InvalidInput(format!("Unknown config key '{key}'")))?;
+            let invalid = || AppError::InvalidInput(format!("Invalid value '{value}' for {key}"));
+            let new_value: toml_edit::Value = if current.is_string() {
+                value.as_str().into()
+            } else if current.is_u64() {
+                value.parse::<i64>().map_err(|_| invalid())?.into()
+            } else {
+                value.parse::<f64>().ok().filter(|v| v.is_finite()).ok_or_else(invalid)?.into()
+            };
+
+            let path = if *user {
+                config::user_config_path()
+                    .ok_or_else(|| AppError::InvalidInput("Cannot locate the user config directory".to_string()))?
+            } else {
+                config::config_path()
+            };
+            let previous = std::fs::read_to_string(&path).ok();
+            let mut file = config::ConfigFile::load(&path)?;
+            file.set(key, new_value);
+            file.save()?;
+
+            // Reject values that make the merged configuration invalid
+            let merged = match Config::load(data_flag, profile) {
+                Ok(merged) => merged,
+                Err(e) => {
+                    match previous {
+                        Some(content) => std::fs::write(&path, content)?,
+                        None => std::fs::remove_file(&path)?,
+                    }
+                    return Err(e);
+                }
+            };
+            Ok(success_json(serde_json::json!({
+                "key": key,
+                "value": merged.to_flat_json().get(key),
+                "file": file.path().display().to_string(),
+                "origin": merged.origins.get(key),
+            })))
+        }
+    }
+}
+
+fn cmd_report(
+    config: &Config,
+    format: &str,
+    period: &str,
+    output: Option<&std::path::Path>,
+) -> Result<serde_json::Value, AppError> {
+    let days = report::period_days(period)
+        .ok_or_else(|| AppError::InvalidInput(format!("Unknown period '{period}'")))?;
+    let db = storage::load_existing(&config.data, &config.srs)?;
+    let report = report::build(&db.items, &config.srs, period, days, Utc::now());
+    let content = match format {
+        "html" => report::render_html(&report),
+        _ => report::render_markdown(&report),
+    };
+
+    let mut response = serde_json::json!({
+        "format": format,
+        "period": period,
+        "summary": {
+            "items_added": report.added.len(),
+            "reviews": report.reviews,
+            "accuracy_pct": report.accuracy(),
+            "newly_mastered": report.newly_mastered.len(),
+            "weak": report.weak.len(),
+        },
+    });
+    match output {
+        Some(path) => {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(path, content)?;
+            response["path"] = serde_json::json!(path.display().to_string());
+        }
+        None => response["content"] = serde_json::json!(content),
+    }
+    Ok(success_json(response))
+}
+
+fn cmd_stats(config: &Config) -> Result<serde_json::Value, AppError> {
+    let db = storage::load_existing(&config.data, &config.srs)?;
     let now = Utc::now();
 
     let total = db.items.len();
@@ -295,6 +1618,17 @@ fn cmd_stats(data_path: &std::path::Path) -> Result<serde_json::Value, AppError>
         *by_type.entry(item.item_type.as_str()).or_insert(0) += 1;
     }
 
+    // Median answer time by type, over reviews that recorded one
+    let mut response_times: HashMap<&str, Vec<u64>> = HashMap::new();
+    for item in &db.items {
+        let times = item.reviews.iter().filter_map(|r| r.response_ms);
+        response_times.entry(item.item_type.as_str()).or_default().extend(times);
+    }
+    let median_response_ms: std::collections::BTreeMap<&str, u64> = response_times
+        .into_iter()
+        .filter_map(|(item_type, mut times)| median(&mut times).map(|m| (item_type, m)))
+        .collect();
+
     // Next due
     let next_due = db
         .items
@@ -314,15 +1648,151 @@ fn cmd_stats(data_path: &std::path::Path) -> Result<serde_json::Value, AppError>
             "mastered": by_status.get("mastered").unwrap_or(&0),
         },
         "by_type": by_type,
+        "median_response_ms": median_response_ms,
         "next_due": next_due,
     })))
 }
 
+fn cmd_forecast(config: &Config, days: usize) -> Result<serde_json::Value, AppError> {
+    let db = storage::load_existing(&config.data, &config.srs)?;
+    let forecast = forecast::build(&db.items, days, Utc::now());
+
+    let bucket_json = |bucket: &forecast::Bucket| {
+        serde_json::json!({
+            "due": bucket.due,
+            "by_status": bucket.by_status,
+            "by_deck": bucket.by_deck,
+        })
+    };
+    let mut cumulative = forecast.overdue.due;
+    let daily: Vec<serde_json::Value> = forecast
+        .days
+        .iter()
+        .map(|(date, bucket)| {
+            cumulative += bucket.due;
+            let mut day = bucket_json(bucket);
+            day["date"] = serde_json::json!(date.to_string());
+            day["cumulative"] = serde_json::json!(cumulative);
+            day
+        })
+        .collect();
+
+    Ok(success_json(serde_json::json!({
+        "days": days,
+        "total_items": db.items.len(),
+        "overdue": bucket_json(&forecast.overdue),
+        "forecast": daily,
+        "beyond": forecast.beyond,
+    })))
+}
+
+fn cmd_activity(config: &Config, goal: usize, draw: bool) -> Result<serde_json::Value, AppError> {
+    let db = storage::load_existing(&config.data, &config.srs)?;
+    let activity = activity::build(&db.items, Utc::now());
+    let today = activity.reviews_on(activity.today);
+    let daily: serde_json::Map<String, serde_json::Value> = activity
+        .daily
+        .iter()
+        .map(|(date, count)| (date.to_string(), serde_json::json!(count)))
+        .collect();
+    let mut response = serde_json::json!({
+        "current_streak": activity.current_streak,
+        "longest_streak": activity.longest_streak,
+        "today": {
+            "date": activity.today.to_string(),
+            "reviews": today,
+            "goal": goal,
+            "remaining": goal.saturating_sub(today),
+            "met": today >= goal,
+        },
+        "from": activity.first_day().to_string(),
+        "total_reviews": activity.total_reviews,
+        "active_days": activity.active_days,
+        "daily": daily,
+    });
+    if draw {
+        response["heatmap"] = serde_json::json!(activity::heatmap(&activity));
+    }
+    Ok(success_json(response))
+}
+
+fn cmd_analytics(config: &Config, action: &cli::AnalyticsAction) -> Result<serde_json::Value, AppError> {
+    let cli::AnalyticsAction::Retention { days } = action;
+    let db = storage::load_existing(&config.data, &config.srs)?;
+    let since = days.map(|d| Utc::now() - chrono::Duration::days(d as i64));
+    let outcomes = analytics::outcomes(&db.items, since);
+    let report = analytics::retention(&outcomes);
+
+    let tally_json = |tally: &analytics::Tally| {
+        serde_json::json!({
+            "reviews": tally.reviews,
+            "correct": tally.correct,
+            "retention_pct": tally.retention(),
+            "predicted_pct": tally.predicted(),
+        })
+    };
+    let grouped = |groups: &std::collections::BTreeMap<String, analytics::Tally>| {
+        groups
+            .iter()
+            .map(|(key, tally)| (key.clone(), tally_json(tally)))
+            .collect::<serde_json::Map<_, _>>()
+    };
+    let by_interval: Vec<serde_json::Value> = report
+        .by_interval
+        .iter()
+        .filter(|(_, tally)| tally.reviews > 0)
+        .map(|(label, tally)| {
+            let mut json = tally_json(tally);
+            json["interval"] = serde_json::json!(label);
+            json
+        })
+        .collect();
+    let calibration: Vec<serde_json::Value> = report
+        .calibration
+        .iter()
+        .map(|(lower, tally)| {
+            let mut json = tally_json(tally);
+            json["predicted_range"] = serde_json::json!(format!("{lower}-{}%", lower + 10));
+            json
+        })
+        .collect();
+
+    Ok(success_json(serde_json::json!({
+        "since": since.map(|s| s.to_rfc3339()),
+        "overall": tally_json(&report.overall),
+        "by_interval": by_interval,
+        "by_type": grouped(&report.by_type),
+        "by_source": grouped(&report.by_source),
+        "calibration": calibration,
+        "brier": report.brier,
+    })))
+}
+
+/// Median of the values (mean of the middle two for an even count).
+fn median(values: &mut [u64]) -> Option<u64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_unstable();
+    let mid = values.len() / 2;
+    Some(if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2
+    } else {
+        values[mid]
+    })
+}
+
 /// Find item by ID prefix. Returns mutable ref or error.
 fn find_item_by_prefix<'a>(
     items: &'a mut [LearningItem],
     prefix: &str,
 ) -> Result<&'a mut LearningItem, AppError> {
+    let idx = find_index_by_prefix(items, prefix)?;
+    Ok(&mut items[idx])
+}
+
+/// Find the index of the item whose ID starts with `prefix`.
+fn find_index_by_prefix(items: &[LearningItem], prefix: &str) -> Result<usize, AppError> {
     let matches: Vec<usize> = items
         .iter()
         .enumerate()
@@ -332,12 +1802,43 @@ fn find_item_by_prefix<'a>(
 
     match matches.len() {
         0 => Err(AppError::NotFound(prefix.to_string())),
-        1 => Ok(&mut items[matches[0]]),
+        1 => Ok(matches[0]),
         _ => Err(AppError::AmbiguousId(prefix.to_string())),
     }
 }
 
-fn generate_id(now: &chrono::DateTime<Utc>, existing_count: usize) -> String {
+/// Build a fresh, never-reviewed item due tomorrow.
+fn new_item(input: &NewItem, id: String, now: chrono::DateTime<Utc>, params: &sm2::Params) -> LearningItem {
+    let item_type = input
+        .item_type
+        .clone()
+        .unwrap_or_else(|| auto_detect_type(&input.front));
+
+    LearningItem {
+        id,
+        item_type,
+        front: input.front.clone(),
+        back: input.back.clone(),
+        context: input.context.clone(),
+        context_ja: input.context_ja.clone(),
+        source: input.source.clone(),
+        created_at: now,
+        last_quizzed: None,
+        next_review: sm2::next_review(&now, 1.0), // Review tomorrow
+        times_quizzed: 0,
+        times_correct: 0,
+        ease_factor: params.initial_ease,
+        interval_days: 0.0,
+        status: "new".to_string(),
+        tags: input.tags.clone(),
+        related_forms: Vec::new(),
+        reviews: Vec::new(),
+        cloze_of: None,
+        extra: HashMap::new(),
+    }
+}
+
+pub(crate) fn generate_id(now: &chrono::DateTime<Utc>, existing_count: usize) -> String {
     let date = now.format("%Y%m%d");
     format!("item_{date}_{:03}", existing_count + 1)
 }
@@ -376,4 +1877,11 @@ mod tests {
         let now = Utc.with_ymd_and_hms(2026, 2, 6, 10, 0, 0).unwrap();
         assert_eq!(generate_id(&now, 15), "item_20260206_016");
     }
+
+    #[test]
+    fn test_median() {
+        assert_eq!(median(&mut []), None);
+        assert_eq!(median(&mut [3000, 1000, 2000]), Some(2000));
+        assert_eq!(median(&mut [4000, 1000, 2000, 3000]), Some(2500));
+    }
 }
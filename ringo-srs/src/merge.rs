@@ -0,0 +1,282 @@
+use crate::dedup::normalize_front;
+use crate::models::{LearningItem, ReviewRecord, SrsDatabase};
+use chrono::{DateTime, Utc};
+
+/// A differing content field between matched items.
+#[derive(Debug, Clone)]
+pub struct Conflict {
+    pub id: String,
+    pub front: String,
+    pub field: &'static str,
+    pub ours: String,
+    pub theirs: String,
+}
+
+/// What happened to one item of the other database.
+#[derive(Debug, Clone)]
+pub struct MergeOutcome {
+    /// ID in the merged database
+    pub id: String,
+    pub front: String,
+    /// "added", "merged", or "unchanged"
+    pub outcome: &'static str,
+}
+
+#[derive(Debug, Default)]
+pub struct MergeReport {
+    pub outcomes: Vec<MergeOutcome>,
+    pub conflicts: Vec<Conflict>,
+}
+
+/// Merge `theirs` into `ours`.
+///
+/// Items match when they share an ID and normalized front, or failing that a
+/// normalized front. Unmatched items are added (with a fresh ID if theirs is
+/// taken). For matched items:
+/// - scheduling state comes from the side reviewed most recently,
+/// - review logs are combined and review counts recomputed so shared history
+///   is not counted twice,
+/// - differing back/context/context_ja are reported as conflicts and resolved
+///   in favour of ours (or theirs with `prefer_theirs`); a missing value is filled in,
+/// - tags, related forms and extra fields from both sides are kept.
+pub fn merge(ours: &mut SrsDatabase, theirs: SrsDatabase, prefer_theirs: bool, now: &DateTime<Utc>) -> MergeReport {
+    let mut report = MergeReport::default();
+
+    for other in theirs.items {
+        let key = normalize_front(&other.front);
+        let matched = ours
+            .items
+            .iter()
+            .position(|i| i.id == other.id && normalize_front(&i.front) == key)
+            .or_else(|| ours.items.iter().position(|i| normalize_front(&i.front) == key));
+
+        match matched {
+            Some(idx) => {
+                let item = &mut ours.items[idx];
+                let before = serde_json::to_value(&*item).ok();
+                merge_item(item, other, prefer_theirs, &mut report.conflicts);
+                let changed = serde_json::to_value(&*item).ok() != before;
+                report.outcomes.push(MergeOutcome {
+                    id: item.id.clone(),
+                    front: item.front.clone(),
+                    outcome: if changed { "merged" } else { "unchanged" },
+                });
+            }
+            None => {
+                let mut item = other;
+                if ours.items.iter().any(|i| i.id == item.id) {
+                    item.id = fresh_id(&ours.items, now);
+                }
+                report.outcomes.push(MergeOutcome {
+                    id: item.id.clone(),
+                    front: item.front.clone(),
+                    outcome: "added",
+                });
+                ours.items.push(item);
+            }
+        }
+    }
+    report
+}
+
+fn fresh_id(items: &[LearningItem], now: &DateTime<Utc>) -> String {
+    (items.len()..)
+        .map(|n| crate::generate_id(now, n))
+        .find(|id| !items.iter().any(|i| &i.id == id))
+        .unwrap()
+}
+
+fn merge_item(ours: &mut LearningItem, theirs: LearningItem, prefer_theirs: bool, conflicts: &mut Vec<Conflict>) {
+    // Content fields
+    let id = ours.id.clone();
+    let front = ours.front.clone();
+    let mut conflict = |field: &'static str, a: &str, b: &str| {
+        conflicts.push(Conflict {
+            id: id.clone(),
+            front: front.clone(),
+            field,
+            ours: a.to_string(),
+            theirs: b.to_string(),
+        });
+    };
+    if ours.back != theirs.back {
+        conflict("back", &ours.back, &theirs.back);
+        if prefer_theirs {
+            ours.back = theirs.back.clone();
+        }
+    }
+    for (field, mine, other) in [
+        ("context", &mut ours.context, theirs.context.clone()),
+        ("context_ja", &mut ours.context_ja, theirs.context_ja.clone()),
+        ("source", &mut ours.source, theirs.source.clone()),
+    ] {
+        match (mine.as_deref(), other.as_deref()) {
+            (None, Some(_)) => *mine = other,
+            (Some(a), Some(b)) if a != b => {
+                if field != "source" {
+                    conflict(field, a, b);
+                }
+                if prefer_theirs {
+                    *mine = other;
+                }
+            }
+            _ => {}
+        }
+    }
+    ours.created_at = ours.created_at.min(theirs.created_at);
+    for tag in theirs.tags {
+        if !ours.tags.contains(&tag) {
+            ours.tags.push(tag);
+        }
+    }
+    for form in theirs.related_forms {
+        if !ours.related_forms.contains(&form) {
+            ours.related_forms.push(form);
+        }
+    }
+    for (key, value) in theirs.extra {
+        if prefer_theirs {
+            ours.extra.insert(key, value);
+        } else {
+            ours.extra.entry(key).or_insert(value);
+        }
+    }
+
+    // Review counts: unlogged (pre-history) reviews are shared; logged ones are combined
+    let unlogged_quizzed = ours
+        .times_quizzed
+        .saturating_sub(ours.reviews.len() as u32)
+        .max(theirs.times_quizzed.saturating_sub(theirs.reviews.len() as u32));
+    let unlogged_correct = ours
+        .times_correct
+        .saturating_sub(count_correct(&ours.reviews))
+        .max(theirs.times_correct.saturating_sub(count_correct(&theirs.reviews)));
+    let theirs_newer = theirs.last_quizzed > ours.last_quizzed;
+
+    let mut reviews = std::mem::take(&mut ours.reviews);
+    for review in &theirs.reviews {
+        if !reviews.iter().any(|r| r.at == review.at && r.result == review.result) {
+            reviews.push(review.clone());
+        }
+    }
+    reviews.sort_by_key(|r| r.at);
+    ours.times_quizzed = unlogged_quizzed + reviews.len() as u32;
+    ours.times_correct = (unlogged_correct + count_correct(&reviews)).min(ours.times_quizzed);
+    ours.reviews = reviews;
+
+    // Scheduling state from the most recently reviewed side
+    if theirs_newer {
+        ours.last_quizzed = theirs.last_quizzed;
+        ours.next_review = theirs.next_review;
+        ours.ease_factor = theirs.ease_factor;
+        ours.interval_days = theirs.interval_days;
+        ours.status = theirs.status;
+    }
+}
+
+fn count_correct(reviews: &[ReviewRecord]) -> u32 {
+    reviews.iter().filter(|r| r.result == "correct").count() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn item(id: &str, front: &str, back: &str) -> LearningItem {
+        LearningItem {
+            id: id.to_string(),
+            back: back.to_string(),
+            created_at: Utc.with_ymd_and_hms(2026, 2, 1, 0, 0, 0).unwrap(),
+            next_review: Utc.with_ymd_and_hms(2026, 2, 2, 0, 0, 0).unwrap(),
+            ..LearningItem::test(front)
+        }
+    }
+
+    fn db(items: Vec<LearningItem>) -> SrsDatabase {
+        let mut db = SrsDatabase::new();
+        db.items = items;
+        db
+    }
+
+    #[test]
+    fn test_combines_review_logs() {
+        let t = |d| Utc.with_ymd_and_hms(2026, 2, d, 0, 0, 0).unwrap();
+        // Shared history: one unlogged review plus the day-3 review
+        let mut a = item("item_1", "implement", "実装する");
+        a.reviews = vec![ReviewRecord::test(t(3), "correct", 1.0), ReviewRecord::test(t(5), "correct", 3.0)];
+        a.times_quizzed = 3;
+        a.times_correct = 3;
+        a.last_quizzed = Some(t(5));
+        a.interval_days = 3.0;
+        let mut b = item("item_1", "implement", "実装する");
+        b.reviews = vec![ReviewRecord::test(t(3), "correct", 1.0), ReviewRecord::test(t(6), "incorrect", 1.0)];
+        b.times_quizzed = 3;
+        b.times_correct = 2;
+        b.last_quizzed = Some(t(6));
+        b.interval_days = 1.0;
+        b.next_review = t(7);
+
+        let mut ours = db(vec![a]);
+        let report = merge(&mut ours, db(vec![b]), false, &Utc::now());
+        let merged = &ours.items[0];
+        assert_eq!(report.outcomes[0].outcome, "merged");
+        assert_eq!(merged.reviews.len(), 3);
+        assert_eq!(merged.times_quizzed, 4);
+        assert_eq!(merged.times_correct, 3);
+        assert_eq!(merged.next_review, t(7));
+        assert_eq!(merged.interval_days, 1.0);
+    }
+
+    #[test]
+    fn test_conflicts_and_extra() {
+        let mut a = item("item_1", "break the ice", "場を和ませる");
+        a.extra.insert("mine".to_string(), serde_json::json!(1));
+        let mut b = item("other_id", "Break the ice.", "緊張をほぐす");
+        b.context = Some("He told a joke to break the ice.".to_string());
+        b.extra.insert("theirs".to_string(), serde_json::json!(2));
+        b.tags = vec!["social".to_string()];
+
+        let mut ours = db(vec![a]);
+        let report = merge(&mut ours, db(vec![b]), false, &Utc::now());
+        let merged = &ours.items[0];
+        assert_eq!(ours.items.len(), 1);
+        assert_eq!(merged.back, "場を和ませる");
+        assert_eq!(merged.context.as_deref(), Some("He told a joke to break the ice."));
+        assert!(merged.extra.contains_key("mine") && merged.extra.contains_key("theirs"));
+        assert_eq!(merged.tags, vec!["social"]);
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.conflicts[0].field, "back");
+        assert_eq!(report.conflicts[0].theirs, "緊張をほぐす");
+    }
+
+    #[test]
+    fn test_prefer_theirs() {
+        let a = item("item_1", "implement", "実装する");
+        let b = item("item_1", "implement", "実行する");
+        let mut ours = db(vec![a]);
+        merge(&mut ours, db(vec![b]), true, &Utc::now());
+        assert_eq!(ours.items[0].back, "実行する");
+    }
+
+    #[test]
+    fn test_same_id_different_front_added_with_fresh_id() {
+        let now = Utc.with_ymd_and_hms(2026, 2, 6, 0, 0, 0).unwrap();
+        let a = item("item_20260206_001", "implement", "実装する");
+        let b = item("item_20260206_001", "procrastinate", "先延ばしにする");
+        let mut ours = db(vec![a]);
+        let report = merge(&mut ours, db(vec![b]), false, &now);
+        assert_eq!(ours.items.len(), 2);
+        assert_eq!(report.outcomes[0].outcome, "added");
+        assert_eq!(ours.items[1].id, "item_20260206_002");
+    }
+
+    #[test]
+    fn test_identical_unchanged() {
+        let a = item("item_1", "implement", "実装する");
+        let mut ours = db(vec![a.clone()]);
+        let report = merge(&mut ours, db(vec![a]), false, &Utc::now());
+        assert_eq!(report.outcomes[0].outcome, "unchanged");
+        assert!(report.conflicts.is_empty());
+    }
+}
@@ -41,11 +41,38 @@ pub struct LearningItem {
     pub interval_days: f64,
     #[serde(default = "default_status")]
     pub status: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Other inflected forms of this front linked via `add --link`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub related_forms: Vec<String>,
+    /// Review log, oldest first. Absent for items created before logging existed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reviews: Vec<ReviewRecord>,
+    /// For cloze cards: ID of the item whose context sentence they blank
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cloze_of: Option<String>,
     /// Preserve unknown fields for forward compatibility with Claude skills
     #[serde(flatten)]
     pub extra: HashMap<String, serde_json::Value>,
 }
 
+/// One recorded review of an item
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewRecord {
+    pub at: DateTime<Utc>,
+    pub result: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<String>,
+    /// Interval scheduled by this review
+    pub interval_days: f64,
+    /// Ease factor after this review
+    pub ease_factor: f64,
+    /// Time taken to answer, when the client measured it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_ms: Option<u64>,
+}
+
 impl LearningItem {
     pub fn accuracy(&self) -> Option<f64> {
         if self.times_quizzed == 0 {
@@ -54,6 +81,71 @@ impl LearningItem {
             Some(self.times_correct as f64 / self.times_quizzed as f64 * 100.0)
         }
     }
+
+    /// Full JSON representation: all stored fields (including `extra`)
+    /// plus the derived `accuracy` rounded to one decimal. Fields omitted on
+    /// disk when empty are included as null or [], so every key is present.
+    pub fn to_full_json(&self) -> serde_json::Map<String, serde_json::Value> {
+        let mut map = match serde_json::to_value(self) {
+            Ok(serde_json::Value::Object(map)) => map,
+            _ => serde_json::Map::new(),
+        };
+        for key in ["context", "context_ja", "source", "cloze_of"] {
+            map.entry(key).or_insert(serde_json::Value::Null);
+        }
+        for key in ["tags", "related_forms", "reviews"] {
+            map.entry(key).or_insert_with(|| serde_json::json!([]));
+        }
+        let accuracy = self.accuracy().map(|acc| (acc * 10.0).round() / 10.0);
+        map.insert("accuracy".to_string(), serde_json::json!(accuracy));
+        map
+    }
+}
+
+#[cfg(test)]
+impl LearningItem {
+    /// Test fixture: a new word item with `front` as its ID, created and due
+    /// now. Tests override the fields they care about with struct update syntax.
+    pub fn test(front: &str) -> Self {
+        let now = Utc::now();
+        LearningItem {
+            id: front.to_string(),
+            item_type: "word".to_string(),
+            front: front.to_string(),
+            back: "訳".to_string(),
+            context: None,
+            context_ja: None,
+            source: None,
+            created_at: now,
+            last_quizzed: None,
+            next_review: now,
+            times_quizzed: 0,
+            times_correct: 0,
+            ease_factor: 2.5,
+            interval_days: 0.0,
+            status: "new".to_string(),
+            tags: Vec::new(),
+            related_forms: Vec::new(),
+            reviews: Vec::new(),
+            cloze_of: None,
+            extra: HashMap::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+impl ReviewRecord {
+    /// Test fixture: a review without difficulty or response time.
+    pub fn test(at: DateTime<Utc>, result: &str, interval_days: f64) -> Self {
+        ReviewRecord {
+            at,
+            result: result.to_string(),
+            difficulty: None,
+            interval_days,
+            ease_factor: 2.5,
+            response_ms: None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -66,6 +158,12 @@ pub struct SrsDatabase {
     pub items: Vec<LearningItem>,
 }
 
+impl Default for SrsDatabase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl SrsDatabase {
     pub fn new() -> Self {
         SrsDatabase {
@@ -76,6 +174,24 @@ impl SrsDatabase {
     }
 }
 
+/// Caller-supplied fields for a new item (from `add` flags, an import row,
+/// or a batch add stdin JSON array element)
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct NewItem {
+    pub front: String,
+    pub back: String,
+    #[serde(default, rename = "type")]
+    pub item_type: Option<String>,
+    #[serde(default)]
+    pub context: Option<String>,
+    #[serde(default)]
+    pub context_ja: Option<String>,
+    #[serde(default)]
+    pub source: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
 /// Input for batch review (stdin JSON array element)
 #[derive(Debug, Deserialize)]
 pub struct ReviewInput {
@@ -83,4 +199,7 @@ pub struct ReviewInput {
     pub result: String,
     #[serde(default)]
     pub difficulty: Option<String>,
+    /// Time taken to answer in milliseconds
+    #[serde(default)]
+    pub response_ms: Option<u64>,
 }
@@ -0,0 +1,265 @@
+use crate::lemma;
+use crate::models::LearningItem;
+use crate::sm2;
+use chrono::{DateTime, Utc};
+use std::str::FromStr;
+
+/// Which pool a planned item was drawn from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    New,
+    Review,
+    Weak,
+}
+
+impl Kind {
+    pub fn name(self) -> &'static str {
+        match self {
+            Kind::New => "new",
+            Kind::Review => "review",
+            Kind::Weak => "weak",
+        }
+    }
+}
+
+/// Relative share of new, review and weak items, written `NEW:REVIEW:WEAK`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mix {
+    pub new: usize,
+    pub review: usize,
+    pub weak: usize,
+}
+
+impl Default for Mix {
+    fn default() -> Self {
+        Mix { new: 1, review: 3, weak: 1 }
+    }
+}
+
+impl FromStr for Mix {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<usize> = s
+            .split(':')
+            .map(|p| p.trim().parse::<usize>())
+            .collect::<Result<_, _>>()
+            .map_err(|_| format!("invalid mix '{s}': expected NEW:REVIEW:WEAK, e.g. 1:3:1"))?;
+        match parts[..] {
+            [new, review, weak] if new + review + weak > 0 => Ok(Mix { new, review, weak }),
+            [_, _, _] => Err("mix ratios cannot all be zero".to_string()),
+            _ => Err(format!("invalid mix '{s}': expected NEW:REVIEW:WEAK, e.g. 1:3:1")),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Planned<'a> {
+    pub item: &'a LearningItem,
+    pub kind: Kind,
+}
+
+/// Plan a study session of up to `size` items.
+///
+/// Pools: new items (never reviewed, oldest first), due reviews (due order)
+/// and weak items (due ones first, then lowest accuracy). Each pool gets its
+/// share of `size` by `mix`; a pool that runs short hands its slots to the
+/// others. The result is interleaved so the pools are spread through the
+/// session, consecutive items differ in type where possible, and siblings
+/// (same lemma, same context sentence, or cloze cards of the same item) are
+/// never adjacent unless nothing else is left.
+pub fn plan<'a>(
+    items: &'a [LearningItem],
+    size: usize,
+    mix: Mix,
+    params: &sm2::Params,
+    now: &DateTime<Utc>,
+) -> Vec<Planned<'a>> {
+    let mut new: Vec<&LearningItem> = items.iter().filter(|i| i.times_quizzed == 0).collect();
+    new.sort_by_key(|i| i.created_at);
+    let mut weak: Vec<&LearningItem> = items
+        .iter()
+        .filter(|i| i.times_quizzed > 0 && params.is_weak(i.times_quizzed, i.accuracy()))
+        .collect();
+    weak.sort_by(|a, b| {
+        (a.next_review > *now)
+            .cmp(&(b.next_review > *now))
+            .then(a.accuracy().partial_cmp(&b.accuracy()).unwrap())
+    });
+    let mut review: Vec<&LearningItem> = items
+        .iter()
+        .filter(|i| i.times_quizzed > 0 && i.next_review <= *now && !params.is_weak(i.times_quizzed, i.accuracy()))
+        .collect();
+    review.sort_by(|a, b| {
+        a.next_review
+            .cmp(&b.next_review)
+            .then(a.ease_factor.partial_cmp(&b.ease_factor).unwrap())
+    });
+
+    let pools = [(Kind::New, new), (Kind::Review, review), (Kind::Weak, weak)];
+    let available = [pools[0].1.len(), pools[1].1.len(), pools[2].1.len()];
+    let quotas = quotas(size, [mix.new, mix.review, mix.weak], available);
+
+    // Spread the pools evenly: each item gets a position in [0, 1) within its pool's share
+    let mut chosen: Vec<(f64, Planned)> = Vec::new();
+    for ((kind, pool), quota) in pools.into_iter().zip(quotas) {
+        for (idx, item) in pool.into_iter().take(quota).enumerate() {
+            chosen.push(((idx as f64 + 0.5) / quota as f64, Planned { item, kind }));
+        }
+    }
+    chosen.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    interleave(chosen.into_iter().map(|(_, p)| p).collect())
+}
+
+/// Split `size` by `weights`, capped by each pool's `available` count;
+/// slots a pool cannot fill go to the others.
+fn quotas(size: usize, weights: [usize; 3], available: [usize; 3]) -> [usize; 3] {
+    let mut quotas = [0; 3];
+    let mut remaining = size.min(available.iter().sum());
+    while remaining > 0 {
+        let open: Vec<usize> = (0..3).filter(|&k| quotas[k] < available[k]).collect();
+        let total_weight: usize = open.iter().map(|&k| weights[k]).sum();
+        let step = remaining;
+        if total_weight == 0 {
+            // Only zero-weight pools are left: fill them in order
+            for &k in &open {
+                let take = remaining.min(available[k] - quotas[k]);
+                quotas[k] += take;
+                remaining -= take;
+            }
+            break;
+        }
+        // Largest remainder: floor shares first, then the biggest fractions
+        let mut shares: Vec<(usize, usize, usize)> = open
+            .iter()
+            .map(|&k| {
+                let exact = step * weights[k];
+                (k, exact / total_weight, exact % total_weight)
+            })
+            .collect();
+        let mut leftover = step - shares.iter().map(|s| s.1).sum::<usize>();
+        shares.sort_by(|a, b| b.2.cmp(&a.2).then(a.0.cmp(&b.0)));
+        for share in shares.iter_mut().filter(|s| weights[s.0] > 0) {
+            if leftover == 0 {
+                break;
+            }
+            share.1 += 1;
+            leftover -= 1;
+        }
+        for (k, share, _) in shares {
+            let take = share.min(available[k] - quotas[k]);
+            quotas[k] += take;
+            remaining -= take;
+        }
+    }
+    quotas
+}
+
+/// Reorder so that neighbours are not siblings and, where possible, differ in
+/// type. Greedy: take the first remaining item that fits best after the last.
+fn interleave(mut rest: Vec<Planned>) -> Vec<Planned> {
+    let mut out: Vec<Planned> = Vec::with_capacity(rest.len());
+    while !rest.is_empty() {
+        let pick = match out.last() {
+            None => 0,
+            Some(last) => {
+                let fits = |p: &Planned| !are_siblings(last.item, p.item);
+                rest.iter()
+                    .position(|p| fits(p) && p.item.item_type != last.item.item_type)
+                    .or_else(|| rest.iter().position(fits))
+                    .unwrap_or(0)
+            }
+        };
+        out.push(rest.remove(pick));
+    }
+    out
+}
+
+/// Items that give each other away when shown back-to-back.
+pub fn are_siblings(a: &LearningItem, b: &LearningItem) -> bool {
+    let same_parent = a.cloze_of.is_some() && a.cloze_of == b.cloze_of;
+    let parent_of = a.cloze_of.as_deref() == Some(b.id.as_str()) || b.cloze_of.as_deref() == Some(a.id.as_str());
+    let same_context = match (&a.context, &b.context) {
+        (Some(x), Some(y)) => x.trim() == y.trim(),
+        _ => false,
+    };
+    same_parent || parent_of || same_context || lemma::shared_lemma(&a.front, &b.front).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn item(id: &str, item_type: &str, front: &str, quizzed: u32, correct: u32, due_days: i64) -> LearningItem {
+        let base = LearningItem::test(front);
+        LearningItem {
+            id: id.to_string(),
+            item_type: item_type.to_string(),
+            next_review: base.created_at + Duration::days(due_days),
+            times_quizzed: quizzed,
+            times_correct: correct,
+            status: "learning".to_string(),
+            ..base
+        }
+    }
+
+    #[test]
+    fn test_mix_parse() {
+        assert_eq!("2:6:2".parse::<Mix>().unwrap(), Mix { new: 2, review: 6, weak: 2 });
+        assert!("1:2".parse::<Mix>().is_err());
+        assert!("0:0:0".parse::<Mix>().is_err());
+        assert!("a:1:1".parse::<Mix>().is_err());
+    }
+
+    #[test]
+    fn test_quotas() {
+        assert_eq!(quotas(10, [1, 3, 1], [10, 10, 10]), [2, 6, 2]);
+        // A short pool hands its slots on
+        assert_eq!(quotas(10, [1, 3, 1], [10, 2, 10]), [4, 2, 4]);
+        assert_eq!(quotas(10, [0, 1, 0], [5, 3, 0]), [5, 3, 0]);
+        assert_eq!(quotas(10, [1, 1, 1], [1, 1, 1]), [1, 1, 1]);
+    }
+
+    #[test]
+    fn test_plan_mix_and_interleave() {
+        let items = vec![
+            item("n1", "idiom", "break the ice", 0, 0, 1),
+            item("n2", "word", "deploy", 0, 0, 1),
+            item("r1", "idiom", "hit the sack", 3, 3, -1),
+            item("r2", "word", "postpone", 3, 3, -2),
+            item("r3", "idiom", "call it a day", 3, 3, -3),
+            item("w1", "word", "procrastinate", 4, 1, 5),
+            item("f1", "word", "refactor", 3, 3, 10),
+        ];
+        let now = Utc::now();
+        let planned = plan(&items, 5, Mix::default(), &sm2::Params::default(), &now);
+        let ids: Vec<&str> = planned.iter().map(|p| p.item.id.as_str()).collect();
+        assert_eq!(planned.len(), 5);
+        // Not-yet-due reviews are never planned
+        assert!(!ids.contains(&"f1"));
+        assert_eq!(planned.iter().filter(|p| p.kind == Kind::Weak).count(), 1);
+        assert_eq!(planned.iter().filter(|p| p.kind == Kind::New).count(), 1);
+        for pair in planned.windows(2) {
+            assert_ne!(pair[0].item.item_type, pair[1].item.item_type, "{ids:?}");
+        }
+    }
+
+    #[test]
+    fn test_siblings_not_adjacent() {
+        let mut a = item("a", "word", "implement", 3, 3, -1);
+        let b = item("b", "word", "implemented", 3, 3, -1);
+        let c = item("c", "word", "deploy", 3, 3, -1);
+        let mut d = item("d", "cloze", "We [...] it.", 0, 0, 1);
+        d.cloze_of = Some("a".to_string());
+        a.context = Some("We implement it.".to_string());
+        assert!(are_siblings(&a, &b));
+        assert!(are_siblings(&a, &d));
+        assert!(!are_siblings(&a, &c));
+
+        let items = vec![a, b, c];
+        let planned = plan(&items, 3, Mix { new: 0, review: 1, weak: 0 }, &sm2::Params::default(), &Utc::now());
+        let ids: Vec<&str> = planned.iter().map(|p| p.item.id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "c", "b"]);
+    }
+}
@@ -0,0 +1,103 @@
+use crate::models::LearningItem;
+use crate::report::escape_html;
+
+/// Cards per row and rows per A4 page.
+const COLUMNS: usize = 2;
+const ROWS: usize = 5;
+
+/// Render items as a printable, double-sided HTML flashcard sheet.
+///
+/// Pages alternate fronts and backs. On each back page the cards of every row
+/// are mirrored, so after long-edge duplex printing each back sits directly
+/// behind its front. The last page is padded with blank cards to keep the grid.
+pub fn render(items: &[&LearningItem]) -> String {
+    let per_page = COLUMNS * ROWS;
+    let mut pages = String::new();
+
+    for chunk in items.chunks(per_page) {
+        let mut fronts = String::new();
+        let mut backs = String::new();
+        for row in 0..ROWS {
+            for col in 0..COLUMNS {
+                let front = chunk.get(row * COLUMNS + col);
+                fronts.push_str(&front.map_or_else(blank_card, |i| front_card(i)));
+                let mirrored = chunk.get(row * COLUMNS + (COLUMNS - 1 - col));
+                backs.push_str(&mirrored.map_or_else(blank_card, |i| back_card(i)));
+            }
+        }
+        pages.push_str(&format!("<section class=\"page fronts\">\n{fronts}</section>\n"));
+        pages.push_str(&format!("<section class=\"page backs\">\n{backs}</section>\n"));
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"ja\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>ringo-srs flashcards</title>\n<style>\n\
+         @page {{ size: A4 portrait; margin: 10mm; }}\n\
+         body {{ margin: 0; font-family: sans-serif; }}\n\
+         .page {{ display: grid; grid-template-columns: repeat({COLUMNS}, 1fr); \
+         grid-template-rows: repeat({ROWS}, 1fr); height: 277mm; page-break-after: always; }}\n\
+         .card {{ border: 1px dashed #999; padding: 4mm; display: flex; flex-direction: column; \
+         justify-content: center; align-items: center; text-align: center; overflow: hidden; }}\n\
+         .front .text {{ font-size: 18pt; font-weight: bold; }}\n\
+         .back .text {{ font-size: 14pt; }}\n\
+         .context {{ font-size: 9pt; color: #444; margin-top: 3mm; }}\n\
+         .type {{ font-size: 8pt; color: #888; margin-top: 2mm; }}\n\
+         </style>\n</head>\n<body>\n{pages}</body>\n</html>\n"
+    )
+}
+
+fn front_card(item: &LearningItem) -> String {
+    format!(
+        "<div class=\"card front\"><div class=\"text\">{}</div><div class=\"type\">{}</div></div>\n",
+        escape_html(&item.front),
+        escape_html(&item.item_type)
+    )
+}
+
+fn back_card(item: &LearningItem) -> String {
+    let mut html = format!("<div class=\"card back\"><div class=\"text\">{}</div>", escape_html(&item.back));
+    for ctx in [&item.context, &item.context_ja].into_iter().flatten() {
+        html.push_str(&format!("<div class=\"context\">{}</div>", escape_html(ctx)));
+    }
+    html.push_str("</div>\n");
+    html
+}
+
+fn blank_card() -> String {
+    "<div class=\"card blank\"></div>\n".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(front: &str) -> LearningItem {
+        LearningItem {
+            back: format!("{front}-back"),
+            ..LearningItem::test(front)
+        }
+    }
+
+    #[test]
+    fn test_backs_mirrored_per_row() {
+        let items = [item("a"), item("b"), item("c")];
+        let refs: Vec<&LearningItem> = items.iter().collect();
+        let html = render(&refs);
+        let backs = &html[html.find("page backs").unwrap()..];
+        // Row 1: b then a; row 2: blank then c
+        let b = backs.find("b-back").unwrap();
+        let a = backs.find("a-back").unwrap();
+        let blank = backs.find("card blank").unwrap();
+        let c = backs.find("c-back").unwrap();
+        assert!(b < a && a < blank && blank < c);
+    }
+
+    #[test]
+    fn test_pages_padded() {
+        let items: Vec<LearningItem> = (0..11).map(|n| item(&n.to_string())).collect();
+        let refs: Vec<&LearningItem> = items.iter().collect();
+        let html = render(&refs);
+        assert_eq!(html.matches("<section class=\"page fronts\">").count(), 2);
+        assert_eq!(html.matches("<div class=\"card").count(), 4 * COLUMNS * ROWS);
+    }
+}
@@ -0,0 +1,170 @@
+use crate::answer::normalize_answer;
+use crate::models::LearningItem;
+use crate::study::Card;
+
+/// A multiple-choice question about one item.
+#[derive(Debug)]
+pub struct Question<'a> {
+    pub item: &'a LearningItem,
+    pub prompt: &'a str,
+    pub options: Vec<&'a str>,
+    pub answer_index: usize,
+}
+
+/// Small deterministic PRNG (SplitMix64) so quizzes can be reproduced from a seed.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform-enough integer in `0..n` (n > 0).
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+/// Build multiple-choice questions for the targets, in order, until `limit`
+/// questions exist. Each has up to `options` choices. Distractors are distinct answers of other items in `pool`,
+/// preferring the same `item_type`, then the closest length; ties are broken
+/// at random. Targets without any usable distractor are skipped.
+pub fn choice_questions<'a>(
+    targets: &[&'a LearningItem],
+    pool: &'a [LearningItem],
+    limit: usize,
+    options: usize,
+    reverse: bool,
+    rng: &mut Rng,
+) -> Vec<Question<'a>> {
+    let wanted = options.saturating_sub(1);
+    let mut questions = Vec::new();
+
+    for &item in targets {
+        if questions.len() >= limit {
+            break;
+        }
+        let card = Card::new(item, reverse);
+        let answer_key = normalize_answer(card.answer);
+        let answer_len = card.answer.chars().count();
+
+        // Candidates: one per distinct answer, best first
+        let mut candidates: Vec<(bool, usize, u64, &'a str)> = Vec::new();
+        let mut seen = vec![answer_key];
+        for other in pool.iter().filter(|o| o.id != item.id) {
+            let text = Card::new(other, reverse).answer;
+            let key = normalize_answer(text);
+            if key.is_empty() || seen.contains(&key) {
+                continue;
+            }
+            seen.push(key);
+            let other_type = other.item_type != item.item_type;
+            let len_diff = text.chars().count().abs_diff(answer_len);
+            candidates.push((other_type, len_diff, rng.next_u64(), text));
+        }
+        candidates.sort_by_key(|&(other_type, len_diff, tiebreak, _)| (other_type, len_diff, tiebreak));
+        candidates.truncate(wanted);
+        if candidates.is_empty() {
+            continue;
+        }
+
+        let mut choices: Vec<&'a str> = candidates.into_iter().map(|(_, _, _, text)| text).collect();
+        choices.push(card.answer);
+        rng.shuffle(&mut choices);
+        let answer_index = choices.iter().position(|c| *c == card.answer).unwrap();
+
+        questions.push(Question {
+            item,
+            prompt: card.prompt,
+            options: choices,
+            answer_index,
+        });
+    }
+    questions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: &str, item_type: &str, front: &str, back: &str) -> LearningItem {
+        LearningItem {
+            id: id.to_string(),
+            item_type: item_type.to_string(),
+            back: back.to_string(),
+            ..LearningItem::test(front)
+        }
+    }
+
+    fn deck() -> Vec<LearningItem> {
+        vec![
+            item("1", "word", "implement", "実装する"),
+            item("2", "word", "postpone", "延期する"),
+            item("3", "word", "deploy", "配備する"),
+            item("4", "word", "execute", "実行する"),
+            item("5", "idiom", "break the ice", "場を和ませる"),
+            item("6", "word", "delay", "延期する"),
+        ]
+    }
+
+    #[test]
+    fn test_choice_question() {
+        let deck = deck();
+        let mut rng = Rng::new(7);
+        let questions = choice_questions(&[&deck[0]], &deck, 5, 4, false, &mut rng);
+        let q = &questions[0];
+        assert_eq!(q.prompt, "implement");
+        assert_eq!(q.options.len(), 4);
+        assert_eq!(q.options[q.answer_index], "実装する");
+        // Same-type distractors of the same length win over the idiom; answers are distinct
+        assert!(!q.options.contains(&"場を和ませる"));
+        let mut distinct = q.options.clone();
+        distinct.sort();
+        distinct.dedup();
+        assert_eq!(distinct.len(), 4);
+    }
+
+    #[test]
+    fn test_reverse_and_too_small_deck() {
+        let deck = deck();
+        let mut rng = Rng::new(1);
+        let q = &choice_questions(&[&deck[1]], &deck, 5, 3, true, &mut rng)[0];
+        assert_eq!(q.prompt, "延期する");
+        assert_eq!(q.options[q.answer_index], "postpone");
+        assert_eq!(q.options.len(), 3);
+
+        let single = [item("1", "word", "implement", "実装する")];
+        assert!(choice_questions(&[&single[0]], &single, 5, 4, false, &mut rng).is_empty());
+    }
+
+    #[test]
+    fn test_limit() {
+        let deck = deck();
+        let targets: Vec<&LearningItem> = deck.iter().collect();
+        let mut rng = Rng::new(3);
+        let questions = choice_questions(&targets, &deck, 2, 4, false, &mut rng);
+        assert_eq!(questions.len(), 2);
+        assert_eq!(questions[1].item.id, "2");
+    }
+
+    #[test]
+    fn test_rng_reproducible() {
+        let (mut a, mut b) = (Rng::new(42), Rng::new(42));
+        assert_eq!(a.next_u64(), b.next_u64());
+        assert!(a.below(5) < 5);
+    }
+}
@@ -0,0 +1,345 @@
+use crate::models::LearningItem;
+use crate::sm2;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+
+/// Number of weak items listed in a report.
+const WEAK_LIMIT: usize = 5;
+
+/// Length in days of a report period name ("day", "week", "month").
+pub fn period_days(period: &str) -> Option<i64> {
+    match period {
+        "day" => Some(1),
+        "week" => Some(7),
+        "month" => Some(30),
+        _ => None,
+    }
+}
+
+/// Aggregated figures for one report period.
+#[derive(Debug)]
+pub struct Report<'a> {
+    pub period: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub total_items: usize,
+    pub added: Vec<&'a LearningItem>,
+    pub reviews: usize,
+    pub correct: usize,
+    /// Reviews and correct answers in the preceding period of the same length
+    pub previous: (usize, usize),
+    /// (date, reviews, correct) for each day of the period
+    pub daily: Vec<(NaiveDate, usize, usize)>,
+    pub newly_mastered: Vec<&'a LearningItem>,
+    pub weak: Vec<&'a LearningItem>,
+}
+
+impl Report<'_> {
+    pub fn accuracy(&self) -> Option<f64> {
+        percent(self.correct, self.reviews)
+    }
+
+    pub fn previous_accuracy(&self) -> Option<f64> {
+        percent(self.previous.1, self.previous.0)
+    }
+}
+
+fn percent(part: usize, total: usize) -> Option<f64> {
+    (total > 0).then(|| (part as f64 / total as f64 * 1000.0).round() / 10.0)
+}
+
+/// Build a report for the `days` ending at `now`.
+pub fn build<'a>(
+    items: &'a [LearningItem],
+    params: &sm2::Params,
+    period: &str,
+    days: i64,
+    now: DateTime<Utc>,
+) -> Report<'a> {
+    let start = now - Duration::days(days);
+    let prev_start = start - Duration::days(days);
+    let in_period = |at: &DateTime<Utc>| *at > start && *at <= now;
+
+    let added: Vec<&LearningItem> = items.iter().filter(|i| in_period(&i.created_at)).collect();
+
+    let first_day = (start + Duration::days(1)).date_naive();
+    let mut daily: Vec<(NaiveDate, usize, usize)> = (0..days)
+        .map(|d| (first_day + Duration::days(d), 0, 0))
+        .collect();
+    let (mut reviews, mut correct, mut prev_reviews, mut prev_correct) = (0, 0, 0, 0);
+    for review in items.iter().flat_map(|i| &i.reviews) {
+        let ok = review.result == "correct";
+        if in_period(&review.at) {
+            reviews += 1;
+            correct += ok as usize;
+            if let Some(day) = daily.iter_mut().find(|d| d.0 == review.at.date_naive()) {
+                day.1 += 1;
+                day.2 += ok as usize;
+            }
+        } else if review.at > prev_start && review.at <= start {
+            prev_reviews += 1;
+            prev_correct += ok as usize;
+        }
+    }
+
+    // Mastered items whose first review reaching the mastered interval fell in the period
+    let newly_mastered: Vec<&LearningItem> = items
+        .iter()
+        .filter(|i| i.status == "mastered")
+        .filter(|i| {
+            i.reviews
+                .iter()
+                .find(|r| r.interval_days >= params.mastered_interval_days)
+                .is_some_and(|r| in_period(&r.at))
+        })
+        .collect();
+
+    let mut weak: Vec<&LearningItem> = items
+        .iter()
+        .filter(|i| params.is_weak(i.times_quizzed, i.accuracy()))
+        .collect();
+    weak.sort_by(|a, b| {
+        a.accuracy()
+            .unwrap_or(0.0)
+            .total_cmp(&b.accuracy().unwrap_or(0.0))
+            .then(b.times_quizzed.cmp(&a.times_quizzed))
+    });
+    weak.truncate(WEAK_LIMIT);
+
+    Report {
+        period: period.to_string(),
+        start,
+        end: now,
+        total_items: items.len(),
+        added,
+        reviews,
+        correct,
+        previous: (prev_reviews, prev_correct),
+        daily,
+        newly_mastered,
+        weak,
+    }
+}
+
+fn fmt_pct(value: Option<f64>) -> String {
+    value.map_or("-".to_string(), |v| format!("{v:.1}%"))
+}
+
+fn item_accuracy(item: &LearningItem) -> String {
+    fmt_pct(item.accuracy().map(|a| (a * 10.0).round() / 10.0))
+}
+
+/// Render the report as Markdown.
+pub fn render_markdown(report: &Report) -> String {
+    let mut out = String::new();
+    out.push_str("# 🍎 ringo-srs study report\n\n");
+    out.push_str(&format!(
+        "Period: {} – {} ({})\n\n",
+        report.start.format("%Y-%m-%d"),
+        report.end.format("%Y-%m-%d"),
+        report.period
+    ));
+
+    out.push_str("## Summary\n\n| | |\n|---|---|\n");
+    out.push_str(&format!("| Items added | {} |\n", report.added.len()));
+    out.push_str(&format!("| Reviews done | {} |\n", report.reviews));
+    out.push_str(&format!(
+        "| Accuracy | {} (previous {}: {}) |\n",
+        fmt_pct(report.accuracy()),
+        report.period,
+        fmt_pct(report.previous_accuracy())
+    ));
+    out.push_str(&format!("| Newly mastered | {} |\n", report.newly_mastered.len()));
+    out.push_str(&format!("| Total items | {} |\n\n", report.total_items));
+
+    out.push_str("## Accuracy trend\n\n| Date | Reviews | Accuracy |\n|---|---:|---:|\n");
+    for (date, reviews, correct) in &report.daily {
+        out.push_str(&format!("| {date} | {reviews} | {} |\n", fmt_pct(percent(*correct, *reviews))));
+    }
+    out.push('\n');
+
+    out.push_str("## Newly mastered\n\n");
+    if report.newly_mastered.is_empty() {
+        out.push_str("_None this period._\n");
+    }
+    for item in &report.newly_mastered {
+        out.push_str(&format!("- **{}** — {}\n", item.front, item.back));
+    }
+    out.push('\n');
+
+    out.push_str("## Weak items\n\n");
+    if report.weak.is_empty() {
+        out.push_str("_No weak items._\n");
+    }
+    for item in &report.weak {
+        out.push_str(&format!(
+            "- **{}** — {} (accuracy {}, {} quizzes)\n",
+            item.front,
+            item.back,
+            item_accuracy(item),
+            item.times_quizzed
+        ));
+        if let Some(ctx) = &item.context {
+            out.push_str(&format!("  - {ctx}\n"));
+        }
+        if let Some(ctx) = &item.context_ja {
+            out.push_str(&format!("  - {ctx}\n"));
+        }
+    }
+    out
+}
+
+pub(crate) fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Render the report as a standalone HTML page.
+pub fn render_html(report: &Report) -> String {
+    let mut body = String::new();
+    body.push_str("<h1>🍎 ringo-srs study report</h1>\n");
+    body.push_str(&format!(
+        "<p>Period: {} – {} ({})</p>\n",
+        report.start.format("%Y-%m-%d"),
+        report.end.format("%Y-%m-%d"),
+        escape_html(&report.period)
+    ));
+
+    body.push_str("<h2>Summary</h2>\n<table>\n");
+    let rows = [
+        ("Items added", report.added.len().to_string()),
+        ("Reviews done", report.reviews.to_string()),
+        (
+            "Accuracy",
+            format!(
+                "{} (previous {}: {})",
+                fmt_pct(report.accuracy()),
+                escape_html(&report.period),
+                fmt_pct(report.previous_accuracy())
+            ),
+        ),
+        ("Newly mastered", report.newly_mastered.len().to_string()),
+        ("Total items", report.total_items.to_string()),
+    ];
+    for (label, value) in rows {
+        body.push_str(&format!("<tr><th>{label}</th><td>{value}</td></tr>\n"));
+    }
+    body.push_str("</table>\n");
+
+    body.push_str("<h2>Accuracy trend</h2>\n<table>\n<tr><th>Date</th><th>Reviews</th><th>Accuracy</th></tr>\n");
+    for (date, reviews, correct) in &report.daily {
+        body.push_str(&format!(
+            "<tr><td>{date}</td><td>{reviews}</td><td>{}</td></tr>\n",
+            fmt_pct(percent(*correct, *reviews))
+        ));
+    }
+    body.push_str("</table>\n");
+
+    body.push_str("<h2>Newly mastered</h2>\n");
+    if report.newly_mastered.is_empty() {
+        body.push_str("<p><em>None this period.</em></p>\n");
+    } else {
+        body.push_str("<ul>\n");
+        for item in &report.newly_mastered {
+            body.push_str(&format!(
+                "<li><strong>{}</strong> — {}</li>\n",
+                escape_html(&item.front),
+                escape_html(&item.back)
+            ));
+        }
+        body.push_str("</ul>\n");
+    }
+
+    body.push_str("<h2>Weak items</h2>\n");
+    if report.weak.is_empty() {
+        body.push_str("<p><em>No weak items.</em></p>\n");
+    } else {
+        body.push_str("<ul>\n");
+        for item in &report.weak {
+            body.push_str(&format!(
+                "<li><strong>{}</strong> — {} (accuracy {}, {} quizzes)",
+                escape_html(&item.front),
+                escape_html(&item.back),
+                item_accuracy(item),
+                item.times_quizzed
+            ));
+            for ctx in [&item.context, &item.context_ja].into_iter().flatten() {
+                body.push_str(&format!("<br><small>{}</small>", escape_html(ctx)));
+            }
+            body.push_str("</li>\n");
+        }
+        body.push_str("</ul>\n");
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>ringo-srs study report</title>\n<style>\n\
+         body {{ font-family: sans-serif; max-width: 48em; margin: 2em auto; }}\n\
+         table {{ border-collapse: collapse; }}\n\
+         th, td {{ border: 1px solid #ccc; padding: 0.3em 0.8em; text-align: left; }}\n\
+         </style>\n</head>\n<body>\n{body}</body>\n</html>\n"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ReviewRecord;
+    use chrono::TimeZone;
+
+    fn item(front: &str, created_at: DateTime<Utc>, results: &[(DateTime<Utc>, &str, f64)]) -> LearningItem {
+        let reviews: Vec<ReviewRecord> = results
+            .iter()
+            .map(|&(at, result, interval_days)| ReviewRecord::test(at, result, interval_days))
+            .collect();
+        LearningItem {
+            context: Some("A <context> sentence.".to_string()),
+            created_at,
+            last_quizzed: reviews.last().map(|r| r.at),
+            next_review: created_at,
+            times_quizzed: reviews.len() as u32,
+            times_correct: reviews.iter().filter(|r| r.result == "correct").count() as u32,
+            interval_days: reviews.last().map_or(0.0, |r| r.interval_days),
+            status: "learning".to_string(),
+            reviews,
+            ..LearningItem::test(front)
+        }
+    }
+
+    #[test]
+    fn test_build_counts() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap();
+        let day = |d: u32| Utc.with_ymd_and_hms(2026, 3, d, 9, 0, 0).unwrap();
+        let mut mastered = item("mastered", day(1), &[(day(2), "correct", 20.0), (day(8), "correct", 45.0)]);
+        mastered.status = "mastered".to_string();
+        let items = vec![
+            mastered,
+            item("weak", day(1), &[(day(2), "incorrect", 1.0), (day(9), "incorrect", 1.0), (day(9), "correct", 1.0)]),
+            item("fresh", day(10), &[]),
+        ];
+
+        let report = build(&items, &sm2::Params::default(), "week", 7, now);
+        assert_eq!(report.added.len(), 1);
+        assert_eq!(report.reviews, 3);
+        assert_eq!(report.correct, 2);
+        assert_eq!(report.previous, (2, 1));
+        assert_eq!(report.daily.len(), 7);
+        assert_eq!(report.daily.last().unwrap().0, now.date_naive());
+        assert_eq!(report.newly_mastered[0].front, "mastered");
+        assert_eq!(report.weak[0].front, "weak");
+    }
+
+    #[test]
+    fn test_render_escapes_html() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap();
+        let day = Utc.with_ymd_and_hms(2026, 3, 9, 9, 0, 0).unwrap();
+        let items = vec![item("a<b", day, &[(day, "incorrect", 1.0), (day, "incorrect", 1.0)])];
+        let report = build(&items, &sm2::Params::default(), "week", 7, now);
+        let html = render_html(&report);
+        assert!(html.contains("<strong>a&lt;b</strong>"));
+        assert!(html.contains("A &lt;context&gt; sentence."));
+        let md = render_markdown(&report);
+        assert!(md.contains("- **a<b** — 訳 (accuracy 0.0%, 2 quizzes)"));
+    }
+}
@@ -0,0 +1,170 @@
+use crate::models::LearningItem;
+
+/// Minimum score for a result to be reported.
+pub const MIN_SCORE: f64 = 0.6;
+
+/// A scored search hit.
+#[derive(Debug, Clone)]
+pub struct SearchHit<'a> {
+    pub item: &'a LearningItem,
+    pub score: f64,
+    /// Name of the field that produced the best score
+    pub field: &'static str,
+}
+
+/// Search items, returning hits with score >= MIN_SCORE, best first.
+///
+/// English queries are matched fuzzily (edit distance against word windows)
+/// on front, back and context. Queries containing Japanese are matched by
+/// substring on front, back, context and context_ja.
+pub fn search<'a>(items: &'a [LearningItem], query: &str) -> Vec<SearchHit<'a>> {
+    let query = fold(query);
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let japanese = query.chars().any(is_japanese);
+
+    let mut hits: Vec<SearchHit> = items
+        .iter()
+        .filter_map(|item| {
+            let fields: [(&'static str, Option<&str>, f64); 4] = [
+                ("front", Some(item.front.as_str()), 1.0),
+                ("back", Some(item.back.as_str()), 0.95),
+                ("context", item.context.as_deref(), 0.85),
+                ("context_ja", item.context_ja.as_deref(), 0.85),
+            ];
+            fields
+                .iter()
+                .filter_map(|&(name, text, weight)| {
+                    let text = fold(text?);
+                    let score = if japanese {
+                        substring_score(&text, &query)
+                    } else {
+                        fuzzy_score(&text, &query)
+                    };
+                    Some((name, score * weight))
+                })
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .filter(|&(_, score)| score >= MIN_SCORE)
+                .map(|(field, score)| SearchHit { item, score, field })
+        })
+        .collect();
+
+    hits.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.item.id.cmp(&b.item.id)));
+    hits
+}
+
+/// Lowercase and collapse whitespace.
+fn fold(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn is_japanese(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}' // hiragana, katakana
+        | '\u{3400}'..='\u{4DBF}' // CJK extension A
+        | '\u{4E00}'..='\u{9FFF}' // CJK unified ideographs
+        | '\u{FF66}'..='\u{FF9F}' // half-width katakana
+    )
+}
+
+/// Substring score: 1.0 for an exact match, otherwise the share of the
+/// field covered by the query, mapped into 0.6..0.9.
+fn substring_score(text: &str, query: &str) -> f64 {
+    if text == query {
+        1.0
+    } else if text.contains(query) {
+        let coverage = query.chars().count() as f64 / text.chars().count() as f64;
+        0.6 + 0.3 * coverage
+    } else {
+        0.0
+    }
+}
+
+/// Fuzzy score: exact and substring matches score like `substring_score`;
+/// otherwise the best normalized edit-distance similarity between the query
+/// and any window of the text with the same number of words.
+fn fuzzy_score(text: &str, query: &str) -> f64 {
+    let exact = substring_score(text, query);
+    if exact > 0.0 {
+        return exact;
+    }
+    let words: Vec<&str> = text
+        .split(|c: char| c.is_whitespace() || (c.is_ascii_punctuation() && c != '\'' && c != '-'))
+        .filter(|w| !w.is_empty())
+        .collect();
+    let n = query.split(' ').count();
+    if words.is_empty() {
+        return 0.0;
+    }
+    let best = words
+        .windows(n.min(words.len()))
+        .map(|window| strsim::normalized_levenshtein(&window.join(" "), query))
+        .fold(0.0, f64::max);
+    // Discount so a near miss scores below an exact match
+    best * 0.9
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: &str, front: &str, back: &str, context: Option<&str>) -> LearningItem {
+        LearningItem {
+            id: id.to_string(),
+            item_type: "phrase".to_string(),
+            back: back.to_string(),
+            context: context.map(String::from),
+            ..LearningItem::test(front)
+        }
+    }
+
+    #[test]
+    fn test_exact_front_ranks_first() {
+        let items = vec![
+            item("a", "put off doing", "先延ばしにする", None),
+            item("b", "put off", "延期する", None),
+        ];
+        let hits = search(&items, "Put Off");
+        assert_eq!(hits[0].item.id, "b");
+        assert_eq!(hits[0].score, 1.0);
+        assert_eq!(hits.len(), 2);
+    }
+
+    #[test]
+    fn test_fuzzy_typo() {
+        let items = vec![item("a", "procrastinate", "先延ばしにする", None)];
+        let hits = search(&items, "procrastnate");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].field, "front");
+    }
+
+    #[test]
+    fn test_fuzzy_in_context() {
+        let items = vec![item("a", "implement", "実装する", Some("We need to put of the release."))];
+        let hits = search(&items, "put off");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].field, "context");
+    }
+
+    #[test]
+    fn test_japanese_substring() {
+        let items = vec![
+            item("a", "procrastinate", "先延ばしにする", None),
+            item("b", "implement", "実装する", None),
+        ];
+        let hits = search(&items, "先延ばし");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].item.id, "a");
+        assert_eq!(hits[0].field, "back");
+    }
+
+    #[test]
+    fn test_unrelated_no_hits() {
+        let items = vec![item("a", "procrastinate", "先延ばしにする", None)];
+        assert!(search(&items, "elephant").is_empty());
+    }
+}
@@ -0,0 +1,168 @@
+use crate::crypto;
+use crate::error::AppError;
+use crate::models::LearningItem;
+use crate::study::{Card, Grade};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A frozen question set spanning several `session answer` calls.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub started_at: DateTime<Utc>,
+    #[serde(default)]
+    pub reverse: bool,
+    pub questions: Vec<Question>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Question {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub item_type: String,
+    pub prompt: String,
+    pub answer: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grade: Option<String>,
+    /// Also the timestamp of the review recorded for this answer, which is
+    /// how an already-applied answer is recognized
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub answered_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_ms: Option<u64>,
+}
+
+impl Question {
+    pub fn new(item: &LearningItem, reverse: bool) -> Self {
+        let card = Card::new(item, reverse);
+        Question {
+            id: item.id.clone(),
+            item_type: item.item_type.clone(),
+            prompt: card.prompt.to_string(),
+            answer: card.answer.to_string(),
+            hint: card.hint.map(str::to_string),
+            grade: None,
+            answered_at: None,
+            response_ms: None,
+        }
+    }
+
+    /// The recorded grade and when it was given, if answered.
+    pub fn answered(&self) -> Option<(Grade, DateTime<Utc>)> {
+        let grade = Grade::parse(self.grade.as_deref()?)?;
+        Some((grade, self.answered_at?))
+    }
+
+    /// Whether `item`'s review log already holds this answer.
+    pub fn is_applied(&self, item: &LearningItem) -> bool {
+        self.answered_at
+            .is_some_and(|at| item.reviews.iter().any(|r| r.at == at))
+    }
+}
+
+impl Session {
+    pub fn new(items: &[&LearningItem], reverse: bool, now: DateTime<Utc>) -> Self {
+        Session {
+            started_at: now,
+            reverse,
+            questions: items.iter().map(|item| Question::new(item, reverse)).collect(),
+        }
+    }
+
+    pub fn answered_count(&self) -> usize {
+        self.questions.iter().filter(|q| q.grade.is_some()).count()
+    }
+
+    /// The question numbered `n` (1-based).
+    pub fn question_mut(&mut self, n: usize) -> Result<&mut Question, AppError> {
+        let total = self.questions.len();
+        n.checked_sub(1)
+            .and_then(|idx| self.questions.get_mut(idx))
+            .ok_or_else(|| AppError::InvalidInput(format!("Question {n} out of range (1-{total})")))
+    }
+}
+
+/// Session file for a data path: `<stem>.session.json` next to a data file,
+/// or `session.json` inside a data directory.
+pub fn path(data: &Path) -> PathBuf {
+    if data.is_dir() {
+        data.join("session.json")
+    } else {
+        data.with_extension("session.json")
+    }
+}
+
+/// The active session, if any. An encrypted session file is opened with
+/// the data file's passphrase.
+pub fn load(path: &Path) -> Result<Option<Session>, AppError> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let raw = fs::read(path)?;
+    let json = if crypto::is_encrypted(&raw) {
+        crypto::decrypt(&raw, &crypto::passphrase()?)?
+    } else {
+        raw
+    };
+    Ok(Some(serde_json::from_slice(&json)?))
+}
+
+/// Write the session; `encrypted` seals it like an encrypted data file so
+/// its prompts and answers never reach the disk in plaintext.
+pub fn save(path: &Path, session: &Session, encrypted: bool) -> Result<(), AppError> {
+    let json = serde_json::to_string_pretty(session)?;
+    let content = if encrypted {
+        crypto::encrypt(json.as_bytes(), &crypto::passphrase()?)?
+    } else {
+        json.into_bytes()
+    };
+    crate::storage::write_atomic(path, &content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ReviewRecord;
+
+    fn item() -> LearningItem {
+        LearningItem {
+            id: "item_1".to_string(),
+            back: "実装する".to_string(),
+            ..LearningItem::test("implement")
+        }
+    }
+
+    #[test]
+    fn test_path() {
+        assert_eq!(
+            path(Path::new("data/learning-items.json")),
+            PathBuf::from("data/learning-items.session.json")
+        );
+    }
+
+    #[test]
+    fn test_question_numbers_and_applied() {
+        let mut item = item();
+        let now = Utc::now();
+        let mut session = Session::new(&[&item], false, now);
+        assert!(session.question_mut(0).is_err());
+        assert!(session.question_mut(2).is_err());
+
+        let q = session.question_mut(1).unwrap();
+        assert_eq!(q.prompt, "implement");
+        q.grade = Some("good".to_string());
+        q.answered_at = Some(now);
+        assert_eq!(q.answered(), Some((Grade::Good, now)));
+        assert!(!q.is_applied(&item));
+
+        item.reviews.push(ReviewRecord {
+            difficulty: Some("good".to_string()),
+            ..ReviewRecord::test(now, "correct", 1.0)
+        });
+        assert!(session.questions[0].is_applied(&item));
+        assert_eq!(session.answered_count(), 1);
+    }
+}
@@ -1,8 +1,95 @@
 use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Scheduler parameters and status thresholds (configurable via `ringo-srs.toml`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Params {
+    /// Ease factor of new items
+    pub initial_ease: f64,
+    /// Ease factor never drops below this
+    pub min_ease: f64,
+    /// Ease added on an "easy" answer
+    pub easy_bonus: f64,
+    /// Ease removed on a "hard" answer
+    pub hard_penalty: f64,
+    /// Ease removed on an incorrect answer
+    pub incorrect_penalty: f64,
+    /// Minimum interval (days) for "reviewing"
+    pub reviewing_interval_days: f64,
+    /// Minimum interval (days) for "mastered"
+    pub mastered_interval_days: f64,
+    /// Minimum accuracy (%) for "mastered"
+    pub mastered_accuracy: f64,
+    /// Minimum number of reviews for "mastered"
+    pub mastered_min_quizzes: u32,
+    /// Items below this accuracy (%) are weak
+    pub weak_accuracy: f64,
+    /// Minimum number of reviews before an item can be weak
+    pub weak_min_quizzes: u32,
+    /// Correct "good" answers slower than this (ms) count as "hard"; 0 disables
+    pub slow_answer_ms: u64,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            initial_ease: 2.5,
+            min_ease: 1.3,
+            easy_bonus: 0.15,
+            hard_penalty: 0.15,
+            incorrect_penalty: 0.2,
+            reviewing_interval_days: 7.0,
+            mastered_interval_days: 30.0,
+            mastered_accuracy: 90.0,
+            mastered_min_quizzes: 5,
+            weak_accuracy: 70.0,
+            weak_min_quizzes: 2,
+            slow_answer_ms: 0,
+        }
+    }
+}
+
+impl Params {
+    /// Reject values that would break scheduling.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.min_ease <= 0.0 || self.initial_ease < self.min_ease {
+            return Err("min_ease must be positive and initial_ease at least min_ease".to_string());
+        }
+        let adjustments = [self.easy_bonus, self.hard_penalty, self.incorrect_penalty];
+        if adjustments.iter().any(|v| *v < 0.0) {
+            return Err("ease adjustments must not be negative".to_string());
+        }
+        if self.reviewing_interval_days <= 0.0 || self.mastered_interval_days < self.reviewing_interval_days {
+            return Err("interval thresholds must be positive and mastered >= reviewing".to_string());
+        }
+        if ![self.mastered_accuracy, self.weak_accuracy].iter().all(|v| (0.0..=100.0).contains(v)) {
+            return Err("accuracy thresholds must be between 0 and 100".to_string());
+        }
+        Ok(())
+    }
+
+    /// Difficulty to schedule with: "good" becomes "hard" when the answer
+    /// took longer than `slow_answer_ms`.
+    pub fn effective_difficulty<'a>(&self, difficulty: &'a str, response_ms: Option<u64>) -> &'a str {
+        let slow = self.slow_answer_ms > 0 && response_ms.is_some_and(|ms| ms > self.slow_answer_ms);
+        if slow && difficulty == "good" {
+            "hard"
+        } else {
+            difficulty
+        }
+    }
+
+    /// Weak: reviewed enough times with accuracy below the threshold.
+    pub fn is_weak(&self, times_quizzed: u32, accuracy: Option<f64>) -> bool {
+        times_quizzed >= self.weak_min_quizzes && accuracy.is_some_and(|acc| acc < self.weak_accuracy)
+    }
+}
 
 /// Apply SM-2 algorithm for a correct answer.
 /// Returns (new_interval_days, new_ease_factor).
 pub fn review_correct(
+    params: &Params,
     times_quizzed: u32,
     interval_days: f64,
     ease_factor: f64,
@@ -17,8 +104,8 @@ pub fn review_correct(
     };
 
     let new_ease = match difficulty {
-        "easy" => ease_factor + 0.15,
-        "hard" => (ease_factor - 0.15).max(1.3),
+        "easy" => ease_factor + params.easy_bonus,
+        "hard" => (ease_factor - params.hard_penalty).max(params.min_ease),
         _ => ease_factor, // "good" or anything else
     };
 
@@ -27,8 +114,8 @@ pub fn review_correct(
 
 /// Apply SM-2 algorithm for an incorrect answer.
 /// Returns (new_interval_days, new_ease_factor).
-pub fn review_incorrect(ease_factor: f64) -> (f64, f64) {
-    let new_ease = (ease_factor - 0.2).max(1.3);
+pub fn review_incorrect(params: &Params, ease_factor: f64) -> (f64, f64) {
+    let new_ease = (ease_factor - params.incorrect_penalty).max(params.min_ease);
     (1.0, new_ease)
 }
 
@@ -38,8 +125,25 @@ pub fn next_review(now: &DateTime<Utc>, interval_days: f64) -> DateTime<Utc> {
     *now + Duration::seconds(secs)
 }
 
+/// Predicted probability of recalling an item at `now`.
+/// Uses an exponential forgetting curve calibrated so that recall is 90%
+/// when the scheduled interval has elapsed. Returns None if never reviewed.
+pub fn recall_probability(
+    interval_days: f64,
+    last_quizzed: Option<&DateTime<Utc>>,
+    now: &DateTime<Utc>,
+) -> Option<f64> {
+    let last = last_quizzed?;
+    if interval_days <= 0.0 {
+        return None;
+    }
+    let elapsed_days = ((*now - *last).num_seconds() as f64 / 86400.0).max(0.0);
+    Some(0.9f64.powf(elapsed_days / interval_days))
+}
+
 /// Compute status based on current state. Used for self-healing.
 pub fn compute_status(
+    params: &Params,
     interval_days: f64,
     accuracy: Option<f64>,
     times_quizzed: u32,
@@ -48,12 +152,12 @@ pub fn compute_status(
 ) -> String {
     if interval_days == 0.0 {
         "new".to_string()
-    } else if interval_days >= 30.0
-        && accuracy.unwrap_or(0.0) >= 90.0
-        && times_quizzed >= 5
+    } else if interval_days >= params.mastered_interval_days
+        && accuracy.unwrap_or(0.0) >= params.mastered_accuracy
+        && times_quizzed >= params.mastered_min_quizzes
     {
         "mastered".to_string()
-    } else if interval_days >= 7.0 {
+    } else if interval_days >= params.reviewing_interval_days {
         "reviewing".to_string()
     } else {
         "learning".to_string()
@@ -66,61 +170,74 @@ mod tests {
 
     #[test]
     fn test_first_correct() {
-        let (interval, ease) = review_correct(0, 0.0, 2.5, "good");
+        let (interval, ease) = review_correct(&Params::default(), 0, 0.0, 2.5, "good");
         assert_eq!(interval, 1.0);
         assert_eq!(ease, 2.5);
     }
 
     #[test]
     fn test_second_correct() {
-        let (interval, ease) = review_correct(1, 1.0, 2.5, "good");
+        let (interval, ease) = review_correct(&Params::default(), 1, 1.0, 2.5, "good");
         assert_eq!(interval, 3.0);
         assert_eq!(ease, 2.5);
     }
 
     #[test]
     fn test_third_correct() {
-        let (interval, ease) = review_correct(2, 3.0, 2.5, "good");
+        let (interval, ease) = review_correct(&Params::default(), 2, 3.0, 2.5, "good");
         assert_eq!(interval, 7.5);
         assert_eq!(ease, 2.5);
     }
 
     #[test]
     fn test_correct_easy() {
-        let (interval, ease) = review_correct(2, 3.0, 2.5, "easy");
+        let (interval, ease) = review_correct(&Params::default(), 2, 3.0, 2.5, "easy");
         assert_eq!(interval, 7.5);
         assert_eq!(ease, 2.65);
     }
 
     #[test]
     fn test_correct_hard() {
-        let (interval, ease) = review_correct(2, 3.0, 2.5, "hard");
+        let (interval, ease) = review_correct(&Params::default(), 2, 3.0, 2.5, "hard");
         assert_eq!(interval, 7.5);
         assert_eq!(ease, 2.35);
     }
 
     #[test]
     fn test_correct_hard_ease_floor() {
-        let (_, ease) = review_correct(2, 3.0, 1.3, "hard");
+        let (_, ease) = review_correct(&Params::default(), 2, 3.0, 1.3, "hard");
         assert_eq!(ease, 1.3);
     }
 
+    #[test]
+    fn test_slow_answer_downgrade() {
+        let params = Params {
+            slow_answer_ms: 10_000,
+            ..Params::default()
+        };
+        assert_eq!(params.effective_difficulty("good", Some(12_000)), "hard");
+        assert_eq!(params.effective_difficulty("good", Some(8_000)), "good");
+        assert_eq!(params.effective_difficulty("easy", Some(12_000)), "easy");
+        assert_eq!(params.effective_difficulty("good", None), "good");
+        assert_eq!(Params::default().effective_difficulty("good", Some(60_000)), "good");
+    }
+
     #[test]
     fn test_incorrect() {
-        let (interval, ease) = review_incorrect(2.5);
+        let (interval, ease) = review_incorrect(&Params::default(), 2.5);
         assert_eq!(interval, 1.0);
         assert!((ease - 2.3).abs() < f64::EPSILON);
     }
 
     #[test]
     fn test_incorrect_ease_floor() {
-        let (_, ease) = review_incorrect(1.3);
+        let (_, ease) = review_incorrect(&Params::default(), 1.3);
         assert_eq!(ease, 1.3);
     }
 
     #[test]
     fn test_incorrect_ease_near_floor() {
-        let (_, ease) = review_incorrect(1.4);
+        let (_, ease) = review_incorrect(&Params::default(), 1.4);
         assert_eq!(ease, 1.3);
     }
 
@@ -132,39 +249,81 @@ mod tests {
         assert_eq!(diff, 3 * 86400);
     }
 
+    #[test]
+    fn test_recall_probability_at_interval() {
+        let now = Utc::now();
+        let last = now - Duration::days(3);
+        let p = recall_probability(3.0, Some(&last), &now).unwrap();
+        assert!((p - 0.9).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_recall_probability_just_reviewed() {
+        let now = Utc::now();
+        assert_eq!(recall_probability(3.0, Some(&now), &now), Some(1.0));
+    }
+
+    #[test]
+    fn test_recall_probability_never_reviewed() {
+        let now = Utc::now();
+        assert_eq!(recall_probability(0.0, None, &now), None);
+    }
+
+    #[test]
+    fn test_custom_params() {
+        let params = Params {
+            min_ease: 2.0,
+            mastered_interval_days: 14.0,
+            ..Params::default()
+        };
+        assert_eq!(review_incorrect(&params, 2.1).1, 2.0);
+        let now = Utc::now();
+        assert_eq!(compute_status(&params, 14.0, Some(95.0), 6, &now, &now), "mastered");
+        assert!(params.validate().is_ok());
+        assert!(Params { initial_ease: 1.5, ..params }.validate().is_err());
+    }
+
+    #[test]
+    fn test_is_weak() {
+        let params = Params::default();
+        assert!(params.is_weak(2, Some(50.0)));
+        assert!(!params.is_weak(1, Some(0.0)));
+        assert!(!params.is_weak(4, Some(75.0)));
+    }
+
     #[test]
     fn test_status_new() {
         let now = Utc::now();
-        assert_eq!(compute_status(0.0, None, 0, &now, &now), "new");
+        assert_eq!(compute_status(&Params::default(), 0.0, None, 0, &now, &now), "new");
     }
 
     #[test]
     fn test_status_learning() {
         let now = Utc::now();
-        assert_eq!(compute_status(3.0, Some(50.0), 2, &now, &now), "learning");
+        assert_eq!(compute_status(&Params::default(), 3.0, Some(50.0), 2, &now, &now), "learning");
     }
 
     #[test]
     fn test_status_reviewing() {
         let now = Utc::now();
-        assert_eq!(compute_status(10.0, Some(75.0), 4, &now, &now), "reviewing");
+        assert_eq!(compute_status(&Params::default(), 10.0, Some(75.0), 4, &now, &now), "reviewing");
     }
 
     #[test]
     fn test_status_mastered() {
         let now = Utc::now();
-        assert_eq!(compute_status(30.0, Some(95.0), 6, &now, &now), "mastered");
+        assert_eq!(compute_status(&Params::default(), 30.0, Some(95.0), 6, &now, &now), "mastered");
     }
 
     #[test]
     fn test_status_not_mastered_low_accuracy() {
         let now = Utc::now();
-        assert_eq!(compute_status(30.0, Some(80.0), 6, &now, &now), "reviewing");
+        assert_eq!(compute_status(&Params::default(), 30.0, Some(80.0), 6, &now, &now), "reviewing");
     }
 
     #[test]
     fn test_status_not_mastered_low_quizzed() {
         let now = Utc::now();
-        assert_eq!(compute_status(30.0, Some(95.0), 4, &now, &now), "reviewing");
+        assert_eq!(compute_status(&Params::default(), 30.0, Some(95.0), 4, &now, &now), "reviewing");
     }
 }
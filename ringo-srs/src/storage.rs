@@ -1,38 +1,107 @@
+use crate::crypto;
 use crate::error::AppError;
 use crate::models::SrsDatabase;
 use crate::sm2;
 use chrono::Utc;
+use std::collections::HashSet;
 use std::fs;
-use std::path::Path;
+use std::io::Read;
+use std::path::{Path, PathBuf};
 use tempfile::NamedTempFile;
 
-/// Load database from JSON file. Returns empty database if file doesn't exist.
-pub fn load(path: &Path) -> Result<SrsDatabase, AppError> {
+/// On-disk layout of a data path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// A single pretty-printed JSON file
+    File,
+    /// A single JSON file encrypted with [`crypto::encrypt`]
+    Encrypted,
+    /// A directory with `meta.json` and one `items/<id>.json` file per item
+    Dir,
+}
+
+impl Layout {
+    /// Layout of an existing path; paths that don't exist yet are plain single files.
+    pub fn of(path: &Path) -> Layout {
+        if path.is_dir() {
+            return Layout::Dir;
+        }
+        let mut head = [0u8; 64];
+        let len = fs::File::open(path).and_then(|mut f| f.read(&mut head)).unwrap_or(0);
+        if crypto::is_encrypted(&head[..len]) {
+            Layout::Encrypted
+        } else {
+            Layout::File
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Layout::File => "file",
+            Layout::Encrypted => "encrypted",
+            Layout::Dir => "dir",
+        }
+    }
+}
+
+const META_FILE: &str = "meta.json";
+const ITEMS_DIR: &str = "items";
+
+/// Load database from a data file or directory. Returns empty database if it doesn't exist.
+pub fn load(path: &Path, params: &sm2::Params) -> Result<SrsDatabase, AppError> {
     if !path.exists() {
         return Ok(SrsDatabase::new());
     }
-    let content = fs::read_to_string(path)?;
-    let mut db: SrsDatabase = serde_json::from_str(&content)?;
-    self_heal(&mut db);
+    let mut db = read(path)?;
+    self_heal(&mut db, params);
     Ok(db)
 }
 
-/// Load database, returning error if the file doesn't exist.
-pub fn load_existing(path: &Path) -> Result<SrsDatabase, AppError> {
+/// Load database, returning error if the data file or directory doesn't exist.
+pub fn load_existing(path: &Path, params: &sm2::Params) -> Result<SrsDatabase, AppError> {
     if !path.exists() {
         return Err(AppError::NoDataFile(path.display().to_string()));
     }
-    let content = fs::read_to_string(path)?;
-    let mut db: SrsDatabase = serde_json::from_str(&content)?;
-    self_heal(&mut db);
+    let mut db = read(path)?;
+    self_heal(&mut db, params);
     Ok(db)
 }
 
-/// Atomic write: write to tempfile, backup existing, then persist.
+fn read(path: &Path) -> Result<SrsDatabase, AppError> {
+    match Layout::of(path) {
+        Layout::File => Ok(serde_json::from_str(&fs::read_to_string(path)?)?),
+        Layout::Encrypted => {
+            let plain = crypto::decrypt(&fs::read(path)?, &crypto::passphrase()?)?;
+            Ok(serde_json::from_slice(&plain)?)
+        }
+        Layout::Dir => read_dir(path),
+    }
+}
+
+/// Save database in the layout already used at `path` (a single file for new paths).
 pub fn save(path: &Path, db: &mut SrsDatabase) -> Result<(), AppError> {
+    save_as(path, db, Layout::of(path))
+}
+
+/// Save database in the given layout.
+pub fn save_as(path: &Path, db: &mut SrsDatabase, layout: Layout) -> Result<(), AppError> {
+    match layout {
+        Layout::File => save_file(path, db, false),
+        Layout::Encrypted => save_file(path, db, true),
+        Layout::Dir => save_dir(path, db),
+    }
+}
+
+/// Atomic write: write to tempfile, backup existing, then persist.
+fn save_file(path: &Path, db: &mut SrsDatabase, encrypted: bool) -> Result<(), AppError> {
     db.last_updated = Some(Utc::now());
 
     let json = serde_json::to_string_pretty(db)?;
+    let content = if encrypted {
+        crypto::encrypt(json.as_bytes(), &crypto::passphrase()?)?
+    } else {
+        json.into_bytes()
+    };
 
     // Ensure parent directory exists
     if let Some(parent) = path.parent() {
@@ -45,29 +114,115 @@ pub fn save(path: &Path, db: &mut SrsDatabase) -> Result<(), AppError> {
         fs::copy(path, &bak)?;
     }
 
-    // Atomic write via tempfile
+    write_atomic(path, &content)
+}
+
+pub(crate) fn write_atomic(path: &Path, content: &[u8]) -> Result<(), AppError> {
     let dir = path.parent().unwrap_or(Path::new("."));
     let tmp = NamedTempFile::new_in(dir)?;
-    fs::write(tmp.path(), json.as_bytes())?;
+    fs::write(tmp.path(), content)?;
     tmp.persist(path).map_err(|e| AppError::IoError(e.error))?;
+    Ok(())
+}
+
+/// Read the directory layout. Items are ordered by file name, which for
+/// generated IDs is creation order.
+fn read_dir(path: &Path) -> Result<SrsDatabase, AppError> {
+    let mut db = SrsDatabase::new();
+    let meta = path.join(META_FILE);
+    if meta.exists() {
+        let value: serde_json::Value = serde_json::from_str(&fs::read_to_string(meta)?)?;
+        if let Some(version) = value.get("version").and_then(|v| v.as_str()) {
+            db.version = version.to_string();
+        }
+    }
+    // The directory layout has no global timestamp
+    db.last_updated = None;
+
+    let items_dir = path.join(ITEMS_DIR);
+    if !items_dir.is_dir() {
+        return Ok(db);
+    }
+    let mut files: Vec<PathBuf> = fs::read_dir(&items_dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<_, _>>()?;
+    files.retain(|f| f.extension().is_some_and(|e| e == "json"));
+    files.sort();
+    for file in files {
+        let content = fs::read_to_string(&file)?;
+        let item = serde_json::from_str(&content)
+            .map_err(|e| AppError::InvalidInput(format!("{}: {e}", file.display())))?;
+        db.items.push(item);
+    }
+    Ok(db)
+}
 
+/// Write the directory layout: one file per item with keys in sorted order.
+/// Only files whose content changed are rewritten, and files of removed items
+/// are deleted, so a save touches exactly the items that changed.
+fn save_dir(path: &Path, db: &mut SrsDatabase) -> Result<(), AppError> {
+    let items_dir = path.join(ITEMS_DIR);
+    fs::create_dir_all(&items_dir)?;
+
+    let meta = format!("{}\n", serde_json::to_string_pretty(&serde_json::json!({ "version": db.version }))?);
+    write_if_changed(&path.join(META_FILE), &meta)?;
+
+    let mut written = HashSet::new();
+    for item in &db.items {
+        let name = format!("{}.json", file_stem(&item.id));
+        if !written.insert(name.clone()) {
+            return Err(AppError::InvalidInput(format!(
+                "Item ID '{}' maps to the same file as another item",
+                item.id
+            )));
+        }
+        // Round-tripping through Value sorts the keys, including flattened extras
+        let json = format!("{}\n", serde_json::to_string_pretty(&serde_json::to_value(item)?)?);
+        write_if_changed(&items_dir.join(name), &json)?;
+    }
+
+    for entry in fs::read_dir(&items_dir)? {
+        let file = entry?.path();
+        let stale = file.extension().is_some_and(|e| e == "json")
+            && file
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| !written.contains(n));
+        if stale {
+            fs::remove_file(file)?;
+        }
+    }
     Ok(())
 }
 
+fn write_if_changed(path: &Path, content: &str) -> Result<(), AppError> {
+    if fs::read_to_string(path).is_ok_and(|existing| existing == content) {
+        return Ok(());
+    }
+    write_atomic(path, content.as_bytes())
+}
+
+/// File name for an item ID: characters outside `[A-Za-z0-9_.-]` become `_`.
+fn file_stem(id: &str) -> String {
+    id.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.') { c } else { '_' })
+        .collect()
+}
+
 /// Validate and self-heal database items.
-fn self_heal(db: &mut SrsDatabase) {
+fn self_heal(db: &mut SrsDatabase, params: &sm2::Params) {
     let now = Utc::now();
     for item in &mut db.items {
-        // Clamp ease_factor to minimum 1.3
-        if item.ease_factor < 1.3 {
-            item.ease_factor = 1.3;
+        // Clamp ease_factor to the configured minimum
+        if item.ease_factor < params.min_ease {
+            item.ease_factor = params.min_ease;
         }
         // Ensure times_correct <= times_quizzed
         if item.times_correct > item.times_quizzed {
             item.times_correct = item.times_quizzed;
         }
         // Recompute status (self-healing)
-        item.status = sm2::compute_status(item.interval_days, item.accuracy(), item.times_quizzed, &now, &item.next_review);
+        item.status = sm2::compute_status(params, item.interval_days, item.accuracy(), item.times_quizzed, &now, &item.next_review);
     }
 }
 
@@ -95,6 +250,10 @@ mod tests {
             ease_factor: 2.5,
             interval_days: 0.0,
             status: "new".to_string(),
+            tags: Vec::new(),
+            related_forms: Vec::new(),
+            reviews: Vec::new(),
+            cloze_of: None,
             extra: HashMap::new(),
         }
     }
@@ -108,7 +267,7 @@ mod tests {
         db.items.push(sample_item());
         save(&path, &mut db).unwrap();
 
-        let loaded = load(&path).unwrap();
+        let loaded = load(&path, &sm2::Params::default()).unwrap();
         assert_eq!(loaded.items.len(), 1);
         assert_eq!(loaded.items[0].front, "test");
     }
@@ -117,7 +276,7 @@ mod tests {
     fn test_load_nonexistent_returns_empty() {
         let dir = TempDir::new().unwrap();
         let path = dir.path().join("nonexistent.json");
-        let db = load(&path).unwrap();
+        let db = load(&path, &sm2::Params::default()).unwrap();
         assert_eq!(db.items.len(), 0);
     }
 
@@ -125,7 +284,7 @@ mod tests {
     fn test_load_existing_nonexistent_returns_error() {
         let dir = TempDir::new().unwrap();
         let path = dir.path().join("nonexistent.json");
-        let result = load_existing(&path);
+        let result = load_existing(&path, &sm2::Params::default());
         assert!(matches!(result, Err(AppError::NoDataFile(_))));
     }
 
@@ -145,6 +304,53 @@ mod tests {
         assert!(bak.exists());
     }
 
+    #[test]
+    fn test_dir_layout_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("deck");
+
+        let mut db = SrsDatabase::new();
+        let mut item = sample_item();
+        item.extra.insert("zeta".to_string(), serde_json::json!(1));
+        db.items.push(item);
+        save_as(&path, &mut db, Layout::Dir).unwrap();
+        assert_eq!(Layout::of(&path), Layout::Dir);
+
+        let content = fs::read_to_string(path.join("items/test_001.json")).unwrap();
+        assert!(content.find("\"back\"").unwrap() < content.find("\"zeta\"").unwrap());
+        assert!(!fs::read_to_string(path.join("meta.json")).unwrap().contains("last_updated"));
+
+        let loaded = load(&path, &sm2::Params::default()).unwrap();
+        assert_eq!(loaded.items.len(), 1);
+        assert_eq!(loaded.items[0].extra["zeta"], 1);
+        assert!(loaded.last_updated.is_none());
+    }
+
+    #[test]
+    fn test_dir_layout_rewrites_only_changed_items() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("deck");
+
+        let mut db = SrsDatabase::new();
+        db.items.push(sample_item());
+        let mut other = sample_item();
+        other.id = "test_002".to_string();
+        db.items.push(other);
+        save_as(&path, &mut db, Layout::Dir).unwrap();
+        let untouched = path.join("items/test_001.json");
+        let mtime = fs::metadata(&untouched).unwrap().modified().unwrap();
+
+        let mut db = load(&path, &sm2::Params::default()).unwrap();
+        db.items[1].back = "changed".to_string();
+        save(&path, &mut db).unwrap();
+        assert_eq!(fs::metadata(&untouched).unwrap().modified().unwrap(), mtime);
+
+        db.items.remove(1);
+        save(&path, &mut db).unwrap();
+        assert!(!path.join("items/test_002.json").exists());
+        assert_eq!(load(&path, &sm2::Params::default()).unwrap().items.len(), 1);
+    }
+
     #[test]
     fn test_self_heal_ease_factor() {
         let dir = TempDir::new().unwrap();
@@ -156,7 +362,7 @@ mod tests {
         db.items.push(item);
         save(&path, &mut db).unwrap();
 
-        let loaded = load(&path).unwrap();
+        let loaded = load(&path, &sm2::Params::default()).unwrap();
         assert!(loaded.items[0].ease_factor >= 1.3);
     }
 
@@ -172,7 +378,7 @@ mod tests {
         db.items.push(item);
         save(&path, &mut db).unwrap();
 
-        let loaded = load(&path).unwrap();
+        let loaded = load(&path, &sm2::Params::default()).unwrap();
         assert_eq!(loaded.items[0].times_correct, loaded.items[0].times_quizzed);
     }
 
@@ -206,14 +412,14 @@ mod tests {
         let path = dir.path().join("test.json");
         fs::write(&path, json).unwrap();
 
-        let mut db = load(&path).unwrap();
+        let mut db = load(&path, &sm2::Params::default()).unwrap();
         assert_eq!(db.items.len(), 1);
         assert_eq!(db.items[0].item_type, "grammar");
 
         save(&path, &mut db).unwrap();
 
         // Verify it can be loaded again
-        let reloaded = load(&path).unwrap();
+        let reloaded = load(&path, &sm2::Params::default()).unwrap();
         assert_eq!(reloaded.items.len(), 1);
         assert_eq!(reloaded.items[0].id, "item_20260204_120001_001");
         assert_eq!(reloaded.items[0].item_type, "grammar");
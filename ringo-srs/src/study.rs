@@ -0,0 +1,423 @@
+use crate::answer::Checked;
+use crate::models::LearningItem;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::style::{Attribute, Print, SetAttribute};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{cursor, execute, queue};
+use std::io::{self, BufRead, Write};
+
+/// Self-assessed grade for one card.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Grade {
+    Again,
+    Hard,
+    Good,
+    Easy,
+}
+
+impl Grade {
+    pub const ALL: [Grade; 4] = [Grade::Again, Grade::Hard, Grade::Good, Grade::Easy];
+
+    /// Keys: 1-4, or the first letter of the grade name.
+    pub fn from_key(key: char) -> Option<Grade> {
+        match key.to_ascii_lowercase() {
+            '1' | 'a' => Some(Grade::Again),
+            '2' | 'h' => Some(Grade::Hard),
+            '3' | 'g' => Some(Grade::Good),
+            '4' | 'e' => Some(Grade::Easy),
+            _ => None,
+        }
+    }
+
+    /// A key or a full grade name ("again", "hard", "good", "easy").
+    pub fn parse(input: &str) -> Option<Grade> {
+        let input = input.trim().to_lowercase();
+        if let Some(grade) = Grade::ALL.into_iter().find(|g| g.name() == input) {
+            return Some(grade);
+        }
+        let mut chars = input.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Grade::from_key(c),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Grade::Again => "again",
+            Grade::Hard => "hard",
+            Grade::Good => "good",
+            Grade::Easy => "easy",
+        }
+    }
+
+    /// Review result recorded for this grade.
+    pub fn result(self) -> &'static str {
+        match self {
+            Grade::Again => "incorrect",
+            _ => "correct",
+        }
+    }
+
+    /// SM-2 difficulty for correct answers.
+    pub fn difficulty(self) -> Option<&'static str> {
+        match self {
+            Grade::Again => None,
+            other => Some(other.name()),
+        }
+    }
+}
+
+/// What a study card shows: the prompt side, the answer side and context.
+#[derive(Debug, Clone, Copy)]
+pub struct Card<'a> {
+    pub prompt: &'a str,
+    pub answer: &'a str,
+    pub item_type: &'a str,
+    pub context: Option<&'a str>,
+    pub context_ja: Option<&'a str>,
+    /// Shown with the prompt: the translation for cloze cards
+    pub hint: Option<&'a str>,
+}
+
+impl<'a> Card<'a> {
+    /// A card asking for the back, or for the front when `reverse`.
+    pub fn new(item: &'a LearningItem, reverse: bool) -> Self {
+        let (prompt, answer) = if reverse {
+            (item.back.as_str(), item.front.as_str())
+        } else {
+            (item.front.as_str(), item.back.as_str())
+        };
+        // A cloze sentence is shown with its translation as a hint
+        let (context_ja, hint) = if item.cloze_of.is_some() && !reverse {
+            (None, item.context_ja.as_deref())
+        } else {
+            (item.context_ja.as_deref(), None)
+        };
+        Card {
+            prompt,
+            answer,
+            item_type: &item.item_type,
+            context: item.context.as_deref(),
+            context_ja,
+            hint,
+        }
+    }
+
+    fn contexts(&self) -> impl Iterator<Item = &'a str> {
+        [self.context, self.context_ja].into_iter().flatten()
+    }
+}
+
+/// Front end of a study session.
+pub trait StudyUi {
+    /// Show the prompt of card `n` of `total`; returns false if the user quits.
+    fn show_front(&mut self, card: &Card, n: usize, total: usize) -> io::Result<bool>;
+
+    /// Reveal the answer and context and ask for a grade; None if the user quits.
+    fn reveal(&mut self, card: &Card, n: usize, total: usize) -> io::Result<Option<Grade>>;
+
+    /// Show the prompt and read a typed answer; None if the user quits.
+    fn type_answer(&mut self, card: &Card, n: usize, total: usize) -> io::Result<Option<String>>;
+
+    /// Show how a typed answer was graded; returns false if the user quits.
+    fn show_checked(
+        &mut self,
+        card: &Card,
+        n: usize,
+        total: usize,
+        checked: &Checked,
+        grade: Grade,
+    ) -> io::Result<bool>;
+}
+
+/// Full-screen terminal front end. The terminal is restored when dropped.
+pub struct TerminalUi {
+    out: io::Stdout,
+}
+
+impl TerminalUi {
+    pub fn new() -> io::Result<Self> {
+        let mut out = io::stdout();
+        terminal::enable_raw_mode()?;
+        execute!(out, EnterAlternateScreen, cursor::Hide)?;
+        Ok(TerminalUi { out })
+    }
+
+    /// Draw the card: the prompt, then the answer and context if revealed,
+    /// then the footer lines.
+    fn draw(
+        &mut self,
+        card: &Card,
+        n: usize,
+        total: usize,
+        revealed: bool,
+        footer: &[String],
+    ) -> io::Result<()> {
+        queue!(self.out, Clear(ClearType::All), cursor::MoveTo(0, 0))?;
+        queue!(self.out, Print(format!("ringo-srs study  [{n}/{total}]  {}\r\n\r\n", card.item_type)))?;
+        queue!(
+            self.out,
+            SetAttribute(Attribute::Bold),
+            Print(format!("  {}\r\n\r\n", card.prompt)),
+            SetAttribute(Attribute::Reset)
+        )?;
+        if let Some(hint) = card.hint {
+            queue!(
+                self.out,
+                SetAttribute(Attribute::Dim),
+                Print(format!("  {hint}\r\n\r\n")),
+                SetAttribute(Attribute::Reset)
+            )?;
+        }
+        if revealed {
+            queue!(self.out, Print(format!("  {}\r\n\r\n", card.answer)))?;
+            for ctx in card.contexts() {
+                queue!(self.out, Print(format!("  {ctx}\r\n")))?;
+            }
+            queue!(self.out, Print("\r\n"))?;
+        }
+        for line in footer {
+            queue!(self.out, Print(format!("{line}\r\n")))?;
+        }
+        self.out.flush()
+    }
+}
+
+impl Drop for TerminalUi {
+    fn drop(&mut self) {
+        let _ = execute!(self.out, cursor::Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Next key press, ignoring releases and non-key events.
+fn next_key() -> io::Result<event::KeyEvent> {
+    loop {
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press {
+                return Ok(key);
+            }
+        }
+    }
+}
+
+fn is_ctrl_c(key: &event::KeyEvent) -> bool {
+    key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL)
+}
+
+/// A key press: Some(char) for keys of interest, None for quit (q, Esc, Ctrl-C).
+fn read_key() -> io::Result<Option<char>> {
+    loop {
+        let key = next_key()?;
+        if is_ctrl_c(&key) {
+            return Ok(None);
+        }
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(None),
+            KeyCode::Char(c) => return Ok(Some(c)),
+            KeyCode::Enter => return Ok(Some('\n')),
+            _ => {}
+        }
+    }
+}
+
+impl StudyUi for TerminalUi {
+    fn show_front(&mut self, card: &Card, n: usize, total: usize) -> io::Result<bool> {
+        self.draw(card, n, total, false, &["[space/enter] reveal    [q] quit".to_string()])?;
+        loop {
+            match read_key()? {
+                None => return Ok(false),
+                Some(' ' | '\n') => return Ok(true),
+                Some(_) => {}
+            }
+        }
+    }
+
+    fn reveal(&mut self, card: &Card, n: usize, total: usize) -> io::Result<Option<Grade>> {
+        let footer = ["[1] again  [2] hard  [3] good  [4] easy    [q] quit".to_string()];
+        self.draw(card, n, total, true, &footer)?;
+        loop {
+            match read_key()? {
+                None => return Ok(None),
+                Some(c) => {
+                    if let Some(grade) = Grade::from_key(c) {
+                        return Ok(Some(grade));
+                    }
+                }
+            }
+        }
+    }
+
+    fn type_answer(&mut self, card: &Card, n: usize, total: usize) -> io::Result<Option<String>> {
+        let mut typed = String::new();
+        loop {
+            let footer = [format!("> {typed}_"), "[enter] check    [esc] quit".to_string()];
+            self.draw(card, n, total, false, &footer)?;
+            let key = next_key()?;
+            if is_ctrl_c(&key) {
+                return Ok(None);
+            }
+            match key.code {
+                KeyCode::Esc => return Ok(None),
+                KeyCode::Enter => return Ok(Some(typed)),
+                KeyCode::Backspace => {
+                    typed.pop();
+                }
+                KeyCode::Char(c) => typed.push(c),
+                _ => {}
+            }
+        }
+    }
+
+    fn show_checked(
+        &mut self,
+        card: &Card,
+        n: usize,
+        total: usize,
+        checked: &Checked,
+        grade: Grade,
+    ) -> io::Result<bool> {
+        let footer = [
+            format!("{} → {}", checked.verdict.name(), grade.name()),
+            "[space/enter] next    [q] quit".to_string(),
+        ];
+        self.draw(card, n, total, true, &footer)?;
+        loop {
+            match read_key()? {
+                None => return Ok(false),
+                Some(' ' | '\n') => return Ok(true),
+                Some(_) => {}
+            }
+        }
+    }
+}
+
+/// Line-based front end for when stdin or stdout is not a terminal: prompts
+/// go to `out` and each answer is one input line. End of input quits.
+pub struct LineUi<R, W> {
+    input: R,
+    out: W,
+}
+
+impl<R: BufRead, W: Write> LineUi<R, W> {
+    pub fn new(input: R, out: W) -> Self {
+        LineUi { input, out }
+    }
+
+    fn read_line(&mut self) -> io::Result<Option<String>> {
+        let mut line = String::new();
+        if self.input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim().to_string();
+        Ok((line != "q").then_some(line))
+    }
+}
+
+impl<R: BufRead, W: Write> StudyUi for LineUi<R, W> {
+    fn show_front(&mut self, card: &Card, n: usize, total: usize) -> io::Result<bool> {
+        writeln!(self.out, "[{n}/{total}] {} ({})", card.prompt, card.item_type)?;
+        if let Some(hint) = card.hint {
+            writeln!(self.out, "  ({hint})")?;
+        }
+        writeln!(self.out, "Press Enter to reveal, q to quit")?;
+        Ok(self.read_line()?.is_some())
+    }
+
+    fn reveal(&mut self, card: &Card, _n: usize, _total: usize) -> io::Result<Option<Grade>> {
+        writeln!(self.out, "  {}", card.answer)?;
+        for ctx in card.contexts() {
+            writeln!(self.out, "  {ctx}")?;
+        }
+        loop {
+            writeln!(self.out, "Grade: 1 again, 2 hard, 3 good, 4 easy (q to quit)")?;
+            let Some(line) = self.read_line()? else { return Ok(None) };
+            if let Some(grade) = Grade::parse(&line) {
+                return Ok(Some(grade));
+            }
+        }
+    }
+    fn type_answer(&mut self, card: &Card, n: usize, total: usize) -> io::Result<Option<String>> {
+        writeln!(self.out, "[{n}/{total}] {} ({})", card.prompt, card.item_type)?;
+        if let Some(hint) = card.hint {
+            writeln!(self.out, "  ({hint})")?;
+        }
+        writeln!(self.out, "Type the answer (q to quit)")?;
+        self.read_line()
+    }
+
+    fn show_checked(
+        &mut self,
+        card: &Card,
+        _n: usize,
+        _total: usize,
+        checked: &Checked,
+        grade: Grade,
+    ) -> io::Result<bool> {
+        writeln!(self.out, "  {} → {}: {}", checked.verdict.name(), grade.name(), card.answer)?;
+        for ctx in card.contexts() {
+            writeln!(self.out, "  {ctx}")?;
+        }
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item() -> LearningItem {
+        LearningItem {
+            id: "item_1".to_string(),
+            back: "実装する".to_string(),
+            context: Some("We need to implement the feature.".to_string()),
+            ..LearningItem::test("implement")
+        }
+    }
+
+    #[test]
+    fn test_grade_parse() {
+        assert_eq!(Grade::parse("3"), Some(Grade::Good));
+        assert_eq!(Grade::parse(" Easy "), Some(Grade::Easy));
+        assert_eq!(Grade::parse("a"), Some(Grade::Again));
+        assert_eq!(Grade::parse("great"), None);
+        assert_eq!(Grade::Again.result(), "incorrect");
+        assert_eq!(Grade::Hard.difficulty(), Some("hard"));
+    }
+
+    #[test]
+    fn test_line_ui() {
+        let mut out = Vec::new();
+        let mut ui = LineUi::new("\nmaybe\n2\n\nq\n".as_bytes(), &mut out);
+        let item = item();
+        let card = Card::new(&item, false);
+        assert!(ui.show_front(&card, 1, 2).unwrap());
+        assert_eq!(ui.reveal(&card, 1, 2).unwrap(), Some(Grade::Hard));
+        assert!(ui.show_front(&card, 2, 2).unwrap());
+        assert_eq!(ui.reveal(&card, 2, 2).unwrap(), None);
+        let shown = String::from_utf8(out).unwrap();
+        assert!(shown.contains("[1/2] implement"));
+        assert!(shown.contains("We need to implement the feature."));
+    }
+
+    #[test]
+    fn test_reverse_card() {
+        let item = item();
+        let card = Card::new(&item, true);
+        assert_eq!(card.prompt, "実装する");
+        assert_eq!(card.answer, "implement");
+    }
+
+    #[test]
+    fn test_cloze_card_hint() {
+        let mut item = item();
+        item.front = "We need to [...] the feature.".to_string();
+        item.context_ja = Some("その機能を実装する必要がある。".to_string());
+        item.cloze_of = Some("item_1".to_string());
+        let card = Card::new(&item, false);
+        assert_eq!(card.hint, Some("その機能を実装する必要がある。"));
+        assert_eq!(card.context_ja, None);
+        assert_eq!(Card::new(&item, true).hint, None);
+    }
+}
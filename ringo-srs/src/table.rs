@@ -0,0 +1,237 @@
+use crate::error::AppError;
+use crate::models::{LearningItem, NewItem};
+
+/// Item fields a column can be mapped to.
+pub const COLUMN_FIELDS: &[&str] = &["front", "back", "type", "context", "context_ja", "source", "tags"];
+
+/// Columns written by `export`.
+const EXPORT_COLUMNS: &[&str] = &["id", "front", "back", "type", "context", "context_ja", "source", "tags"];
+
+/// Field delimiter for a format name ("csv" or "tsv").
+pub fn delimiter(format: &str) -> Result<u8, AppError> {
+    match format {
+        "csv" => Ok(b','),
+        "tsv" => Ok(b'\t'),
+        other => Err(AppError::InvalidInput(format!("Unsupported format '{other}'"))),
+    }
+}
+
+/// A parsed data row: 1-based line number and either the item or why it is invalid.
+pub type Row = (usize, Result<NewItem, String>);
+
+/// Parse delimited text into rows.
+///
+/// Columns are mapped to fields by `columns` (one entry per column, "" or "-"
+/// to skip) or, if absent, by the header row names. Unknown header names are
+/// ignored. With `has_header` the first line is never treated as data.
+pub fn read_rows(
+    input: &str,
+    delimiter: u8,
+    columns: Option<&[String]>,
+    has_header: bool,
+) -> Result<Vec<Row>, AppError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(input.as_bytes());
+    let mut records = reader.records();
+
+    let header = if has_header {
+        match records.next() {
+            Some(record) => Some(record.map_err(csv_error)?),
+            None => return Ok(Vec::new()),
+        }
+    } else {
+        None
+    };
+
+    let mapping: Vec<Option<&str>> = match (columns, &header) {
+        (Some(cols), _) => cols
+            .iter()
+            .map(|c| match c.trim() {
+                "" | "-" => Ok(None),
+                name => field_name(name)
+                    .map(Some)
+                    .ok_or_else(|| AppError::InvalidInput(format!("Unknown column field '{name}'"))),
+            })
+            .collect::<Result<_, _>>()?,
+        (None, Some(header)) => header.iter().map(|h| field_name(h.trim())).collect(),
+        (None, None) => {
+            return Err(AppError::InvalidInput(
+                "--columns is required when the input has no header row".to_string(),
+            ))
+        }
+    };
+    for required in ["front", "back"] {
+        if !mapping.contains(&Some(required)) {
+            return Err(AppError::InvalidInput(format!("No column mapped to '{required}'")));
+        }
+    }
+
+    let first_line = if has_header { 2 } else { 1 };
+    let mut rows = Vec::new();
+    for (idx, record) in records.enumerate() {
+        let record = record.map_err(csv_error)?;
+        if record.iter().all(|v| v.trim().is_empty()) {
+            continue;
+        }
+        let line = record.position().map_or(first_line + idx, |p| p.line() as usize);
+
+        let mut item = NewItem::default();
+        for (value, field) in record.iter().zip(&mapping) {
+            let value = value.trim();
+            let Some(field) = field else { continue };
+            let opt = (!value.is_empty()).then(|| value.to_string());
+            match *field {
+                "front" => item.front = value.to_string(),
+                "back" => item.back = value.to_string(),
+                "type" => item.item_type = opt,
+                "context" => item.context = opt,
+                "context_ja" => item.context_ja = opt,
+                "source" => item.source = opt,
+                "tags" => item.tags = split_tags(value),
+                _ => {}
+            }
+        }
+
+        let row = if item.front.is_empty() || item.back.is_empty() {
+            Err("'front' and 'back' are required".to_string())
+        } else {
+            Ok(item)
+        };
+        rows.push((line, row));
+    }
+    Ok(rows)
+}
+
+/// Render items as delimited text with a header row.
+pub fn write_items(items: &[&LearningItem], delimiter: u8) -> Result<String, AppError> {
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_writer(Vec::new());
+    writer.write_record(EXPORT_COLUMNS).map_err(csv_error)?;
+    for item in items {
+        writer
+            .write_record([
+                item.id.as_str(),
+                &item.front,
+                &item.back,
+                &item.item_type,
+                item.context.as_deref().unwrap_or(""),
+                item.context_ja.as_deref().unwrap_or(""),
+                item.source.as_deref().unwrap_or(""),
+                &join_tags(&item.tags),
+            ])
+            .map_err(csv_error)?;
+    }
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| AppError::IoError(e.into_error()))?;
+    String::from_utf8(bytes).map_err(|e| AppError::InvalidInput(e.to_string()))
+}
+
+/// Map a column/header name to an item field, case-insensitively.
+fn field_name(name: &str) -> Option<&'static str> {
+    let lower = name.to_lowercase().replace(['-', ' '], "_");
+    COLUMN_FIELDS.iter().copied().find(|f| *f == lower)
+}
+
+fn is_tag_separator(c: char) -> bool {
+    c.is_whitespace() || c == ',' || c == ';'
+}
+
+/// Tags may be separated by whitespace, commas or semicolons; a backslash
+/// keeps the next character (a separator or backslash) inside the tag.
+fn split_tags(value: &str) -> Vec<String> {
+    let mut tags = Vec::new();
+    let mut tag = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => tag.push(chars.next().unwrap_or('\\')),
+            c if is_tag_separator(c) => {
+                if !tag.is_empty() {
+                    tags.push(std::mem::take(&mut tag));
+                }
+            }
+            c => tag.push(c),
+        }
+    }
+    if !tag.is_empty() {
+        tags.push(tag);
+    }
+    tags
+}
+
+/// Space-separated tags, escaped so that `split_tags` reads them back unchanged.
+fn join_tags(tags: &[String]) -> String {
+    tags.iter()
+        .map(|tag| {
+            let mut escaped = String::with_capacity(tag.len());
+            for c in tag.chars() {
+                if c == '\\' || is_tag_separator(c) {
+                    escaped.push('\\');
+                }
+                escaped.push(c);
+            }
+            escaped
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn csv_error(e: csv::Error) -> AppError {
+    AppError::InvalidInput(format!("Invalid delimited input: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_with_header() {
+        let input = "Front,Back,Tags,notes\nimplement,実装する,\"work, dev\",x\n\"say \"\"hi\"\"\",挨拶する,,\n";
+        let rows = read_rows(input, b',', None, true).unwrap();
+        assert_eq!(rows.len(), 2);
+        let first = rows[0].1.as_ref().unwrap();
+        assert_eq!(first.front, "implement");
+        assert_eq!(first.tags, vec!["work", "dev"]);
+        assert_eq!(rows[1].1.as_ref().unwrap().front, "say \"hi\"");
+    }
+
+    #[test]
+    fn test_read_with_columns_tsv() {
+        let input = "go shopping\tignored\t買い物に行く\n";
+        let columns = vec!["front".to_string(), "-".to_string(), "back".to_string()];
+        let rows = read_rows(input, b'\t', Some(&columns), false).unwrap();
+        let item = rows[0].1.as_ref().unwrap();
+        assert_eq!(item.front, "go shopping");
+        assert_eq!(item.back, "買い物に行く");
+        assert_eq!(rows[0].0, 1);
+    }
+
+    #[test]
+    fn test_read_invalid_row() {
+        let rows = read_rows("front,back\nhello,\n", b',', None, true).unwrap();
+        assert!(rows[0].1.is_err());
+        assert_eq!(rows[0].0, 2);
+    }
+
+    #[test]
+    fn test_tags_round_trip() {
+        let mut item = LearningItem::test("implement");
+        item.tags = vec!["work, dev".to_string(), "a;b c".to_string(), r"back\slash".to_string(), "plain".to_string()];
+        for delimiter in [b',', b'\t'] {
+            let text = write_items(&[&item], delimiter).unwrap();
+            let rows = read_rows(&text, delimiter, None, true).unwrap();
+            assert_eq!(rows[0].1.as_ref().unwrap().tags, item.tags);
+        }
+        assert_eq!(split_tags(r"a\ b,c"), vec!["a b", "c"]);
+    }
+
+    #[test]
+    fn test_read_missing_back_column() {
+        assert!(read_rows("front,meaning\nhello,x\n", b',', None, true).is_err());
+    }
+}
@@ -3,8 +3,15 @@ use predicates::prelude::*;
 use std::fs;
 use tempfile::TempDir;
 
+#[allow(deprecated)] // assert_cmd 2.1 deprecates cargo_bin in favour of cargo_bin_cmd!
 fn ringo_srs() -> Command {
-    Command::cargo_bin("ringo-srs").unwrap()
+    let mut cmd = Command::cargo_bin("ringo-srs").unwrap();
+    // Keep the developer's own configuration files out of the tests
+    cmd.env("RINGO_SRS_CONFIG", "/nonexistent/ringo-srs.toml")
+        .env("RINGO_SRS_USER_CONFIG", "/nonexistent/config.toml")
+        .env_remove("RINGO_SRS_DATA")
+        .env_remove("RINGO_SRS_PROFILE");
+    cmd
 }
 
 fn setup_empty(dir: &TempDir) -> std::path::PathBuf {
@@ -326,3 +333,1254 @@ fn test_round_trip_preserves_extra_fields() {
     assert!(content.contains("custom_field"));
     assert!(content.contains("should_be_preserved"));
 }
+
+#[test]
+fn test_list_fields() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_with_item(&dir);
+
+    let output = ringo_srs()
+        .args(["--data", path.to_str().unwrap(), "list", "--fields", "id,times_quizzed,accuracy"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let item = json["data"]["items"][0].as_object().unwrap();
+    assert_eq!(item.len(), 3);
+    assert_eq!(item["id"], "item_20260204_001");
+    assert_eq!(item["times_quizzed"], 0);
+    assert!(item["accuracy"].is_null());
+}
+
+#[test]
+fn test_list_fields_unknown() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_with_item(&dir);
+
+    ringo_srs()
+        .args(["--data", path.to_str().unwrap(), "list", "--fields", "id,nope"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid_input"));
+}
+
+#[test]
+fn test_list_full() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_with_item(&dir);
+
+    ringo_srs()
+        .args(["--data", path.to_str().unwrap(), "list", "--full"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""ease_factor": 2.5"#))
+        .stdout(predicate::str::contains(r#""source": "ringo-learning""#))
+        .stdout(predicate::str::contains(r#""accuracy": null"#));
+
+    // Empty fields are still present
+    ringo_srs()
+        .args(["--data", path.to_str().unwrap(), "add", "--front", "deploy", "--back", "配備する"])
+        .assert()
+        .success();
+    let out = ringo_srs()
+        .args(["--data", path.to_str().unwrap(), "list", "--full"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    let item = json["data"]["items"][1].as_object().unwrap();
+    for key in ["context", "context_ja", "source", "cloze_of", "last_quizzed", "accuracy"] {
+        assert_eq!(item[key], serde_json::Value::Null, "{key}");
+    }
+    for key in ["tags", "related_forms", "reviews"] {
+        assert_eq!(item[key], serde_json::json!([]), "{key}");
+    }
+}
+
+#[test]
+fn test_show_item() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_with_item(&dir);
+
+    ringo_srs()
+        .args(["--data", path.to_str().unwrap(), "show", "item_2026"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""id": "item_20260204_001""#))
+        .stdout(predicate::str::contains(r#""due": true"#))
+        .stdout(predicate::str::contains(r#""recall_probability": null"#));
+}
+
+#[test]
+fn test_show_after_review_has_history() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_with_item(&dir);
+    let data = path.to_str().unwrap();
+
+    ringo_srs()
+        .args(["--data", data, "review", "item_20260204_001", "correct", "easy"])
+        .assert()
+        .success();
+
+    let output = ringo_srs().args(["--data", data, "show", "item_20260204_001"]).output().unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let reviews = json["data"]["item"]["reviews"].as_array().unwrap();
+    assert_eq!(reviews.len(), 1);
+    assert_eq!(reviews[0]["result"], "correct");
+    assert_eq!(reviews[0]["difficulty"], "easy");
+    assert_eq!(json["data"]["due"], false);
+    assert_eq!(json["data"]["recall_probability"], 1.0);
+}
+
+#[test]
+fn test_show_not_found() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_with_item(&dir);
+
+    ringo_srs()
+        .args(["--data", path.to_str().unwrap(), "show", "nonexistent"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("not_found"));
+}
+
+#[test]
+fn test_search_fuzzy() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_with_item(&dir);
+
+    ringo_srs()
+        .args(["--data", path.to_str().unwrap(), "search", "implemnt"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""count": 1"#))
+        .stdout(predicate::str::contains(r#""matched_field": "front""#));
+}
+
+#[test]
+fn test_search_japanese() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_with_item(&dir);
+
+    ringo_srs()
+        .args(["--data", path.to_str().unwrap(), "search", "実装"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""id": "item_20260204_001""#));
+}
+
+#[test]
+fn test_add_duplicate_normalized() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_with_item(&dir);
+
+    ringo_srs()
+        .args(["--data", path.to_str().unwrap(), "add", "--front", "To Implement.", "--back", "実装する"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("duplicate"))
+        .stderr(predicate::str::contains(r#""existing_id": "item_20260204_001""#));
+}
+
+#[test]
+fn test_add_duplicate_force() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_with_item(&dir);
+
+    ringo_srs()
+        .args(["--data", path.to_str().unwrap(), "add", "--front", "implement", "--back", "実装する", "--force"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""total_items": 2"#));
+}
+
+#[test]
+fn test_add_near_duplicate_warning() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_with_item(&dir);
+
+    ringo_srs()
+        .args(["--data", path.to_str().unwrap(), "add", "--front", "implements", "--back", "実装する"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""near_duplicates": ["#))
+        .stdout(predicate::str::contains(r#""id": "item_20260204_001""#));
+}
+
+#[test]
+fn test_add_reports_same_lemma() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_with_item(&dir);
+
+    ringo_srs()
+        .args(["--data", path.to_str().unwrap(), "add", "--front", "implemented", "--back", "実装した"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""lemma": "implement""#))
+        .stdout(predicate::str::contains(r#""total_items": 2"#));
+}
+
+#[test]
+fn test_add_link_related_form() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_with_item(&dir);
+    let data = path.to_str().unwrap();
+
+    // The linked form still gets its own card, with everything supplied
+    let output = ringo_srs()
+        .args([
+            "--data", data, "add", "--front", "has implemented", "--back", "実装した", "--context", "It has implemented X.",
+            "--link",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let json = &json["data"];
+    assert_eq!(json["linked_to"], "item_20260204_001");
+    assert_eq!(json["total_items"], 2);
+    let new_id = json["id"].as_str().unwrap().to_string();
+
+    let db: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    let items = db["items"].as_array().unwrap();
+    assert_eq!(items[0]["related_forms"], serde_json::json!(["has implemented"]));
+    assert_eq!(items[1]["related_forms"], serde_json::json!(["implement"]));
+    assert_eq!(items[1]["back"], "実装した");
+    assert_eq!(items[1]["context"], "It has implemented X.");
+
+    ringo_srs()
+        .args(["--data", data, "add", "--front", "Has implemented.", "--back", "実装した"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(format!(r#""existing_id": "{new_id}""#)));
+}
+
+#[test]
+fn test_add_link_ignores_false_stems() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.json");
+    let data = path.to_str().unwrap();
+
+    ringo_srs().args(["--data", data, "add", "--front", "see", "--back", "見る"]).assert().success();
+    let output = ringo_srs()
+        .args(["--data", data, "add", "--front", "seed", "--back", "種", "--link"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let json = &json["data"];
+    assert!(json.get("linked_to").is_none());
+    assert_eq!(json["same_lemma"], serde_json::json!([]));
+    assert_eq!(json["total_items"], 2);
+}
+
+#[test]
+fn test_import_csv() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_with_item(&dir);
+    let csv = dir.path().join("vocab.csv");
+    fs::write(
+        &csv,
+        "front,back,type,tags\nImplement.,実装する(更新),,\nbreak the ice,場を和ませる,idiom,social\n,空,,\n",
+    )
+    .unwrap();
+
+    ringo_srs()
+        .args(["--data", path.to_str().unwrap(), "import", csv.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""added": 1"#))
+        .stdout(predicate::str::contains(r#""skipped": 1"#))
+        .stdout(predicate::str::contains(r#""invalid": 1"#))
+        .stdout(predicate::str::contains(r#""total_items": 2"#));
+}
+
+#[test]
+fn test_import_update_and_dry_run() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_with_item(&dir);
+    let data = path.to_str().unwrap();
+    let tsv = "implement\t実装する(更新)\n";
+
+    ringo_srs()
+        .args(["--data", data, "import", "--format", "tsv", "--no-header", "--columns", "front,back"])
+        .args(["--on-duplicate", "update", "--dry-run"])
+        .write_stdin(tsv)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""updated": 1"#));
+    assert!(!fs::read_to_string(&path).unwrap().contains("更新"));
+
+    ringo_srs()
+        .args(["--data", data, "import", "--format", "tsv", "--no-header", "--columns", "front,back"])
+        .args(["--on-duplicate", "update"])
+        .write_stdin(tsv)
+        .assert()
+        .success();
+    assert!(fs::read_to_string(&path).unwrap().contains("更新"));
+}
+
+#[test]
+fn test_import_fail_on_duplicate() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_with_item(&dir);
+
+    ringo_srs()
+        .args(["--data", path.to_str().unwrap(), "import", "--on-duplicate", "fail"])
+        .write_stdin("front,back\nnew word,新語\nimplement,実装する\n")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("duplicate"));
+    assert!(!fs::read_to_string(&path).unwrap().contains("new word"));
+}
+
+#[test]
+fn test_export_round_trip() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_with_item(&dir);
+    let out = dir.path().join("out.csv");
+
+    ringo_srs()
+        .args(["--data", path.to_str().unwrap(), "export", "--output", out.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""count": 1"#));
+
+    let content = fs::read_to_string(&out).unwrap();
+    assert!(content.starts_with("id,front,back,type,context,context_ja,source,tags\n"));
+    assert!(content.contains("item_20260204_001,implement,実装する,word"));
+
+    let fresh = dir.path().join("fresh.json");
+    ringo_srs()
+        .args(["--data", fresh.to_str().unwrap(), "import", out.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""added": 1"#));
+}
+
+#[test]
+fn test_apkg_round_trip() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_with_item(&dir);
+    let data = path.to_str().unwrap();
+    let apkg = dir.path().join("ringo.apkg");
+
+    ringo_srs()
+        .args(["--data", data, "review", "item_20260204_001", "correct", "easy"])
+        .assert()
+        .success();
+    ringo_srs()
+        .args(["--data", data, "export", "--format", "apkg", "--output", apkg.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""count": 1"#));
+
+    let fresh = dir.path().join("fresh.json");
+    ringo_srs()
+        .args(["--data", fresh.to_str().unwrap(), "import", "--format", "apkg", apkg.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""added": 1"#));
+
+    let db: serde_json::Value = serde_json::from_str(&fs::read_to_string(&fresh).unwrap()).unwrap();
+    let item = &db["items"][0];
+    assert_eq!(item["front"], "implement");
+    assert_eq!(item["context"], "We need to implement the feature.");
+    assert_eq!(item["source"], "anki");
+    assert_eq!(item["times_quizzed"], 1);
+    assert_eq!(item["ease_factor"], 2.65);
+    assert_eq!(item["reviews"][0]["difficulty"], "easy");
+}
+
+#[test]
+fn test_import_not_apkg() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_empty(&dir);
+    let bogus = dir.path().join("bogus.apkg");
+    fs::write(&bogus, "not a zip").unwrap();
+
+    ringo_srs()
+        .args(["--data", path.to_str().unwrap(), "import", "--format", "apkg", bogus.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid_input"));
+}
+
+#[test]
+fn test_add_batch() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_with_item(&dir);
+
+    let output = ringo_srs()
+        .args(["--data", path.to_str().unwrap(), "add"])
+        .write_stdin(
+            r#"[
+                {"front":"eat","back":"食べる","tags":["verbs"]},
+                {"front":"Implement.","back":"実装する"},
+                {"front":"no back"},
+                {"front":"go to the gym","back":"ジムに行く","type":"phrase"}
+            ]"#,
+        )
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let data = &json["data"];
+    assert_eq!(data["summary"]["added"], 2);
+    assert_eq!(data["summary"]["duplicate"], 1);
+    assert_eq!(data["summary"]["invalid"], 1);
+    assert_eq!(data["results"][1]["existing_id"], "item_20260204_001");
+    assert_eq!(data["results"][2]["outcome"], "invalid");
+    assert_eq!(data["total_items"], 3);
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert!(content.contains("go to the gym"));
+    assert!(content.contains("verbs"));
+}
+
+#[test]
+fn test_add_batch_flag_defaults() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_empty(&dir);
+
+    ringo_srs()
+        .args(["--data", path.to_str().unwrap(), "add", "--type", "idiom", "--source", "ringo-explain"])
+        .write_stdin(
+            r#"[
+                {"front":"break the ice","back":"場を和ませる"},
+                {"front":"hit the sack","back":"寝る","type":"phrase","source":"manual"}
+            ]"#,
+        )
+        .assert()
+        .success();
+    let db: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(db["items"][0]["type"], "idiom");
+    assert_eq!(db["items"][0]["source"], "ringo-explain");
+    assert_eq!(db["items"][1]["type"], "phrase");
+    assert_eq!(db["items"][1]["source"], "manual");
+}
+
+#[test]
+fn test_add_batch_invalid_json() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_empty(&dir);
+
+    ringo_srs()
+        .args(["--data", path.to_str().unwrap(), "add"])
+        .write_stdin("not json")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid_input"));
+}
+
+#[test]
+fn test_report_markdown() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_with_item(&dir);
+    let data = path.to_str().unwrap();
+
+    ringo_srs()
+        .args(["--data", data, "review", "item_20260204_001", "correct", "good"])
+        .assert()
+        .success();
+
+    ringo_srs()
+        .args(["--data", data, "report"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""reviews": 1"#))
+        .stdout(predicate::str::contains("# 🍎 ringo-srs study report"));
+}
+
+#[test]
+fn test_report_html_output_file() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_with_item(&dir);
+    let out = dir.path().join("reports").join("week.html");
+
+    ringo_srs()
+        .args(["--data", path.to_str().unwrap(), "report", "--format", "html", "--output", out.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""path""#));
+
+    assert!(fs::read_to_string(&out).unwrap().starts_with("<!DOCTYPE html>"));
+}
+
+#[test]
+fn test_export_print_due_filter() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_with_item(&dir);
+    let data = path.to_str().unwrap();
+
+    ringo_srs()
+        .args(["--data", data, "add", "--front", "procrastinate", "--back", "先延ばしにする"])
+        .assert()
+        .success();
+
+    // Only the overdue item is due now; the new one is due tomorrow
+    let output = ringo_srs()
+        .args(["--data", data, "export", "--format", "print", "--due-within", "0"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["data"]["count"], 1);
+    let html = json["data"]["content"].as_str().unwrap();
+    assert!(html.contains("implement"));
+    assert!(html.contains("機能を実装する必要がある。"));
+    assert!(!html.contains("procrastinate"));
+
+    ringo_srs()
+        .args(["--data", data, "export", "--format", "print", "--due-within", "7"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""count": 2"#));
+}
+
+#[test]
+fn test_merge() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_with_item(&dir);
+    let other = dir.path().join("other.json");
+    fs::write(
+        &other,
+        r#"{
+  "version": "1.0",
+  "items": [
+    {
+      "id": "item_20260204_001",
+      "type": "word",
+      "front": "implement",
+      "back": "実行する",
+      "created_at": "2026-02-04T12:00:00Z",
+      "last_quizzed": "2026-02-05T12:00:00Z",
+      "next_review": "2026-02-08T12:00:00Z",
+      "times_quizzed": 1,
+      "times_correct": 1,
+      "interval_days": 3,
+      "laptop_note": "kept"
+    },
+    {
+      "id": "item_20260204_002",
+      "type": "phrase",
+      "front": "put off",
+      "back": "延期する",
+      "created_at": "2026-02-04T12:00:00Z",
+      "next_review": "2026-02-05T12:00:00Z"
+    }
+  ]
+}"#,
+    )
+    .unwrap();
+
+    ringo_srs()
+        .args(["--data", path.to_str().unwrap(), "merge", other.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""added": 1"#))
+        .stdout(predicate::str::contains(r#""merged": 1"#))
+        .stdout(predicate::str::contains(r#""theirs": "実行する""#));
+
+    let db: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    let items = db["items"].as_array().unwrap();
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0]["back"], "実装する");
+    assert_eq!(items[0]["interval_days"], 3.0);
+    assert_eq!(items[0]["laptop_note"], "kept");
+}
+
+#[test]
+fn test_convert_to_dir_layout() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_with_item(&dir);
+    let deck = dir.path().join("deck");
+    let deck_str = deck.to_str().unwrap();
+
+    ringo_srs()
+        .args(["--data", path.to_str().unwrap(), "convert", deck_str])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""layout": "dir""#))
+        .stdout(predicate::str::contains(r#""count": 1"#));
+    assert!(deck.join("items/item_20260204_001.json").exists());
+
+    // Commands work transparently on the directory
+    ringo_srs()
+        .args(["--data", deck_str, "add", "--front", "put off", "--back", "延期する"])
+        .assert()
+        .success();
+    ringo_srs()
+        .args(["--data", deck_str, "stats"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""total_items": 2"#));
+    assert_eq!(fs::read_dir(deck.join("items")).unwrap().count(), 2);
+
+    // Existing destinations are refused
+    ringo_srs()
+        .args(["--data", path.to_str().unwrap(), "convert", deck_str])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid_input"));
+}
+
+#[test]
+fn test_encrypt_decrypt() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_with_item(&dir);
+    let data = path.to_str().unwrap();
+
+    ringo_srs()
+        .env("RINGO_SRS_PASSPHRASE", "secret")
+        .args(["--data", data, "encrypt"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""layout": "encrypted""#));
+    let raw = fs::read(&path).unwrap();
+    assert!(!String::from_utf8_lossy(&raw).contains("implement"));
+    assert!(!path.with_extension("json.bak").exists());
+
+    // Commands work transparently with the passphrase (from a keyfile here)
+    let keyfile = dir.path().join("key");
+    fs::write(&keyfile, "secret\n").unwrap();
+    ringo_srs()
+        .env("RINGO_SRS_KEYFILE", &keyfile)
+        .args(["--data", data, "review", "item_20260204_001", "correct"])
+        .assert()
+        .success();
+    assert!(!String::from_utf8_lossy(&fs::read(&path).unwrap()).contains("implement"));
+
+    // Missing or wrong passphrase
+    ringo_srs()
+        .args(["--data", data, "stats"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("encryption_error"));
+    ringo_srs()
+        .env("RINGO_SRS_PASSPHRASE", "wrong")
+        .args(["--data", data, "stats"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("wrong passphrase"));
+
+    ringo_srs()
+        .env("RINGO_SRS_PASSPHRASE", "secret")
+        .args(["--data", data, "decrypt"])
+        .assert()
+        .success();
+    let db: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(db["items"][0]["times_quizzed"], 1);
+}
+
+#[test]
+fn test_session_encrypted_with_data_file() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_with_item(&dir);
+    let data = path.to_str().unwrap();
+    let session_file = dir.path().join("learning-items.session.json");
+    let run = |args: &[&str]| {
+        let mut cmd = ringo_srs();
+        cmd.env("RINGO_SRS_PASSPHRASE", "secret").args(["--data", data]).args(args);
+        cmd
+    };
+    let no_plaintext = || {
+        for entry in fs::read_dir(dir.path()).unwrap() {
+            let raw = fs::read(entry.unwrap().path()).unwrap();
+            let text = String::from_utf8_lossy(&raw);
+            assert!(!text.contains("implement") && !text.contains("実装する"));
+        }
+    };
+
+    run(&["encrypt"]).assert().success();
+    run(&["session", "start"]).assert().success();
+    assert!(session_file.exists());
+    no_plaintext();
+
+    run(&["session", "answer", "1", "good"]).assert().success();
+    no_plaintext();
+    run(&["session", "resume"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""answered": 1"#));
+    ringo_srs()
+        .args(["--data", data, "session", "resume"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("encryption_error"));
+
+    // Decrypting the data file also decrypts the active session
+    run(&["decrypt"]).assert().success();
+    assert!(fs::read_to_string(&session_file).unwrap().contains("implement"));
+    ringo_srs().args(["--data", data, "session", "finish"]).assert().success();
+}
+
+#[test]
+fn test_profiles() {
+    let dir = TempDir::new().unwrap();
+    let config = dir.path().join("ringo-srs.toml");
+    let run = |args: &[&str]| {
+        let mut cmd = ringo_srs();
+        cmd.env("RINGO_SRS_CONFIG", &config).args(args);
+        cmd
+    };
+
+    run(&["profiles", "create", "alice"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("profiles/alice.json"));
+    run(&["profiles", "create", "alice"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("already exists"));
+    run(&["profiles", "create", "bob", "--data", "bob.json"]).assert().success();
+
+    run(&["--profile", "alice", "add", "--front", "implement", "--back", "実装する"])
+        .assert()
+        .success();
+    assert!(dir.path().join("data/profiles/alice.json").exists());
+    run(&["--profile", "bob", "stats"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("no_data_file"));
+
+    run(&["profiles", "list", "--profile", "alice"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""count": 2"#))
+        .stdout(predicate::str::contains(r#""active": true"#));
+
+    run(&["profiles", "delete", "alice", "--purge"]).assert().success();
+    assert!(!dir.path().join("data/profiles/alice.json").exists());
+    run(&["--profile", "alice", "stats"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown profile"));
+
+    // An explicit --data never loses silently to --profile
+    run(&["--data", "other.json", "--profile", "bob", "stats"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used together"));
+    run(&["--data", "other.json", "--profile", "bob", "stats"])
+        .env("RINGO_SRS_DATA", "other.json")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used together"));
+    run(&["--data", "other.json", "stats"])
+        .env("RINGO_SRS_PROFILE", "bob")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("other.json"));
+    // Both flags are accepted after the subcommand as well
+    run(&["stats", "--data", "other.json"]).assert().failure().stderr(predicate::str::contains("other.json"));
+    run(&["stats", "--profile", "bob"]).assert().failure().stderr(predicate::str::contains("bob.json"));
+    run(&["--data", "carol.json", "profiles", "create", "carol"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("carol.json"));
+}
+
+#[test]
+fn test_profiles_from_user_and_project_config() {
+    let dir = TempDir::new().unwrap();
+    let project = dir.path().join("ringo-srs.toml");
+    let user_dir = dir.path().join("home");
+    fs::create_dir(&user_dir).unwrap();
+    let user = user_dir.join("config.toml");
+    fs::write(&user, "[profiles.carol]\ndata = \"carol.json\"\n\n[profiles.bob]\ndata = \"bob-user.json\"\n").unwrap();
+    fs::write(&project, "[profiles.bob]\ndata = \"bob.json\"\n").unwrap();
+    let run = |args: &[&str]| {
+        let mut cmd = ringo_srs();
+        cmd.env("RINGO_SRS_CONFIG", &project).env("RINGO_SRS_USER_CONFIG", &user).args(args);
+        cmd
+    };
+
+    let out = run(&["profiles", "list"]).output().unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    let profiles = json["data"]["profiles"].as_array().unwrap();
+    assert_eq!(profiles.len(), 2);
+    assert_eq!(profiles[0]["name"], "carol");
+    assert_eq!(profiles[0]["origin"], "user");
+    assert_eq!(profiles[0]["data"], user_dir.join("carol.json").display().to_string());
+    assert_eq!(profiles[1]["origin"], "project");
+    assert_eq!(profiles[1]["data"], dir.path().join("bob.json").display().to_string());
+
+    run(&["--profile", "carol", "add", "--front", "implement", "--back", "実装する"])
+        .assert()
+        .success();
+    assert!(user_dir.join("carol.json").exists());
+    run(&["profiles", "create", "carol"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("already exists"));
+    run(&["profiles", "delete", "carol"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("user config"));
+}
+
+#[test]
+fn test_config_precedence_and_set() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_with_item(&dir);
+    let project = dir.path().join("ringo-srs.toml");
+    let user = dir.path().join("user.toml");
+    fs::write(&user, "[srs]\nmin_ease = 1.5\nweak_accuracy = 80\n").unwrap();
+    fs::write(&project, "data = \"learning-items.json\"\n\n[srs]\nweak_accuracy = 60\n").unwrap();
+    let run = |args: &[&str]| {
+        let mut cmd = ringo_srs();
+        cmd.env("RINGO_SRS_CONFIG", &project).env("RINGO_SRS_USER_CONFIG", &user).args(args);
+        cmd
+    };
+
+    let out = run(&["config", "show"]).assert().success().get_output().stdout.clone();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    let data = &json["data"];
+    assert_eq!(data["config"]["srs"]["min_ease"], 1.5);
+    assert_eq!(data["config"]["srs"]["weak_accuracy"], 60.0);
+    assert_eq!(data["config"]["srs"]["initial_ease"], 2.5);
+    assert_eq!(data["config"]["data"], path.to_str().unwrap());
+    assert_eq!(data["origins"]["srs.min_ease"], "user");
+    assert_eq!(data["origins"]["srs.weak_accuracy"], "project");
+
+    // The data path from the project file is used by commands; --data overrides it
+    run(&["stats"]).assert().success().stdout(predicate::str::contains(r#""total_items": 1"#));
+    run(&["--data", "elsewhere.json", "config", "get", "data"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""origin": "flag""#));
+
+    run(&["config", "set", "srs.mastered_interval_days", "21"]).assert().success();
+    assert!(fs::read_to_string(&project).unwrap().contains("mastered_interval_days = 21.0"));
+    run(&["config", "set", "srs.mastered_min_quizzes", "2.5"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid_input"));
+    run(&["config", "set", "srs.initial_ease", "1.0"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("initial_ease"));
+    assert!(!fs::read_to_string(&project).unwrap().contains("initial_ease"));
+    run(&["config", "set", "srs.bogus", "1"]).assert().failure();
+
+    // New items use the configured initial ease
+    run(&["config", "set", "srs.initial_ease", "2.0", "--user"]).assert().success();
+    run(&["add", "--front", "put off", "--back", "延期する"]).assert().success();
+    let db: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(db["items"][1]["ease_factor"], 2.0);
+}
+
+#[test]
+fn test_study_line_mode() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_with_item(&dir);
+    let data = path.to_str().unwrap();
+
+    // Reveal, then an unknown grade is re-prompted before "good"
+    ringo_srs()
+        .args(["--data", data, "study"])
+        .write_stdin("\nmaybe\n3\n")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("[1/1] implement"))
+        .stderr(predicate::str::contains("実装する"))
+        .stdout(predicate::str::contains(r#""reviewed": 1"#))
+        .stdout(predicate::str::contains(r#""good": 1"#))
+        .stdout(predicate::str::contains(r#""completed": true"#));
+
+    let db: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(db["items"][0]["times_quizzed"], 1);
+    assert_eq!(db["items"][0]["reviews"][0]["difficulty"], "good");
+
+    // Nothing due any more
+    ringo_srs()
+        .args(["--data", data, "study"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""reviewed": 0"#));
+}
+
+#[test]
+fn test_check_answer() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_with_item(&dir);
+    let data = path.to_str().unwrap();
+
+    ringo_srs()
+        .args(["--data", data, "check", "item_20260204", "To Implement!", "--reverse", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""verdict": "exact""#))
+        .stdout(predicate::str::contains(r#""recorded": false"#));
+
+    ringo_srs()
+        .args(["--data", data, "check", "item_20260204_001", "実行する"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""verdict": "wrong""#))
+        .stdout(predicate::str::contains(r#""grade": "again""#));
+    let db: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(db["items"][0]["times_quizzed"], 1);
+    assert_eq!(db["items"][0]["times_correct"], 0);
+}
+
+#[test]
+fn test_study_typed() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_with_item(&dir);
+
+    ringo_srs()
+        .args(["--data", path.to_str().unwrap(), "study", "--typed", "--reverse"])
+        .write_stdin("implemnt\n")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("[1/1] 実装する"))
+        .stdout(predicate::str::contains(r#""verdict": "close""#))
+        .stdout(predicate::str::contains(r#""hard": 1"#));
+}
+
+#[test]
+fn test_quiz_choice() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_with_item(&dir);
+    let data = path.to_str().unwrap();
+    for (front, back) in [("execute", "実行する"), ("deploy", "配備する"), ("refactor", "リファクタリングする")] {
+        ringo_srs()
+            .args(["--data", data, "add", "--front", front, "--back", back])
+            .assert()
+            .success();
+    }
+
+    // Only the original item is due; distractors come from the whole deck
+    let out = ringo_srs()
+        .args(["--data", data, "quiz", "--mode", "choice", "--n", "5", "--options", "3", "--seed", "9"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    let questions = json["data"]["questions"].as_array().unwrap();
+    assert_eq!(questions.len(), 1);
+    let q = &questions[0];
+    assert_eq!(q["question"], "implement");
+    let options = q["options"].as_array().unwrap();
+    assert_eq!(options.len(), 3);
+    assert_eq!(options[q["answer_index"].as_u64().unwrap() as usize], "実装する");
+    assert!(!options.contains(&serde_json::json!("リファクタリングする")));
+}
+
+#[test]
+fn test_cloze_cards() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_with_item(&dir);
+    let data = path.to_str().unwrap();
+    ringo_srs()
+        .args(["--data", data, "add", "--front", "postpone", "--back", "延期する"])
+        .assert()
+        .success();
+
+    let out = ringo_srs()
+        .args(["--data", data, "cloze"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    assert_eq!(json["data"]["created"], 1);
+    let result = &json["data"]["results"][0];
+    assert_eq!(result["text"], "We need to [...] the feature.");
+    assert_eq!(result["answer"], "implement");
+
+    let db: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    let card = &db["items"][2];
+    assert_eq!(card["type"], "cloze");
+    assert_eq!(card["cloze_of"], "item_20260204_001");
+    assert_eq!(card["context_ja"], "機能を実装する必要がある。");
+    assert_eq!(card["status"], "new");
+
+    // Re-running skips the existing card
+    let out = ringo_srs()
+        .args(["--data", data, "cloze"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    assert_eq!(json["data"]["created"], 0);
+    assert_eq!(json["data"]["existing"], 1);
+}
+
+#[test]
+fn test_session_lifecycle() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_with_item(&dir);
+    let data = path.to_str().unwrap();
+    ringo_srs()
+        .args(["--data", data, "add", "--front", "postpone", "--back", "延期する"])
+        .assert()
+        .success();
+    let mut db: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    db["items"][1]["next_review"] = serde_json::json!("2020-01-02T00:00:00Z");
+    fs::write(&path, db.to_string()).unwrap();
+    let session_file = dir.path().join("learning-items.session.json");
+
+    let out = ringo_srs()
+        .args(["--data", data, "session", "start", "--n", "10"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    assert_eq!(json["data"]["count"], 2);
+    assert_eq!(json["data"]["questions"][0]["question"], "implement");
+    assert!(session_file.exists());
+
+    // Only one active session
+    ringo_srs()
+        .args(["--data", data, "session", "start"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid_input"));
+
+    ringo_srs()
+        .args(["--data", data, "session", "answer", "1", "good"])
+        .assert()
+        .success();
+    ringo_srs()
+        .args(["--data", data, "session", "answer", "1", "again"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("already answered"));
+
+    // An answer saved in the session file but not in the data file (interrupted) is recovered once
+    let mut session: serde_json::Value = serde_json::from_str(&fs::read_to_string(&session_file).unwrap()).unwrap();
+    session["questions"][1]["grade"] = serde_json::json!("again");
+    session["questions"][1]["answered_at"] = serde_json::json!("2026-02-05T00:00:00Z");
+    fs::write(&session_file, session.to_string()).unwrap();
+    let out = ringo_srs()
+        .args(["--data", data, "session", "resume"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    assert_eq!(json["data"]["recovered"], 1);
+    assert_eq!(json["data"]["questions"].as_array().unwrap().len(), 0);
+
+    let out = ringo_srs()
+        .args(["--data", data, "session", "finish"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    assert_eq!(json["data"]["reviewed"], 2);
+    assert_eq!(json["data"]["recovered"], 0);
+    assert_eq!(json["data"]["grades"]["good"], 1);
+    assert_eq!(json["data"]["grades"]["again"], 1);
+    assert!(!session_file.exists());
+
+    let db: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    for item in db["items"].as_array().unwrap() {
+        assert_eq!(item["times_quizzed"], 1);
+    }
+}
+
+#[test]
+fn test_plan_session() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_with_item(&dir);
+    let data = path.to_str().unwrap();
+    for (front, back) in [("implemented", "実装した"), ("break the ice", "場を和ませる"), ("deploy", "配備する")] {
+        ringo_srs()
+            .args(["--data", data, "add", "--front", front, "--back", back, "--force"])
+            .assert()
+            .success();
+    }
+
+    let out = ringo_srs()
+        .args(["--data", data, "plan", "--size", "10", "--mix", "1:3:1"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    // Only new items exist, so they fill every slot
+    assert_eq!(json["data"]["count"], 4);
+    assert_eq!(json["data"]["pools"]["new"], 4);
+    let fronts: Vec<&str> = json["data"]["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|i| i["front"].as_str().unwrap())
+        .collect();
+    let a = fronts.iter().position(|f| *f == "implement").unwrap();
+    let b = fronts.iter().position(|f| *f == "implemented").unwrap();
+    assert!(a.abs_diff(b) > 1, "{fronts:?}");
+
+    ringo_srs()
+        .args(["--data", data, "plan", "--mix", "1:2"])
+        .assert()
+        .failure();
+
+    let out = ringo_srs()
+        .args(["--data", data, "session", "start", "--n", "2", "--mix", "1:0:0"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    assert_eq!(json["data"]["count"], 2);
+}
+
+#[test]
+fn test_review_response_time() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_with_item(&dir);
+    let data = path.to_str().unwrap();
+    fs::write(dir.path().join("ringo-srs.toml"), "[srs]\nslow_answer_ms = 10000\n").unwrap();
+    let config = dir.path().join("ringo-srs.toml");
+
+    let out = ringo_srs()
+        .env("RINGO_SRS_CONFIG", &config)
+        .args(["--data", data, "review", "item_20260204_001", "correct", "good", "--response-ms", "15000"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    assert_eq!(json["data"]["results"][0]["difficulty"], "hard");
+
+    let db: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(db["items"][0]["reviews"][0]["response_ms"], 15000);
+
+    ringo_srs()
+        .args(["--data", data, "review"])
+        .write_stdin(r#"[{"id":"item_20260204_001","result":"correct","response_ms":5000}]"#)
+        .assert()
+        .success();
+    let out = ringo_srs()
+        .args(["--data", data, "stats"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    assert_eq!(json["data"]["median_response_ms"]["word"], 10000);
+}
+
+#[test]
+fn test_forecast() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_with_item(&dir);
+    let data = path.to_str().unwrap();
+    // New items are first due tomorrow
+    ringo_srs()
+        .args(["--data", data, "add", "--front", "postpone", "--back", "延期する"])
+        .assert()
+        .success();
+
+    let out = ringo_srs()
+        .args(["--data", data, "forecast", "--days", "7"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    let data = &json["data"];
+    assert_eq!(data["overdue"]["due"], 1);
+    assert_eq!(data["overdue"]["by_status"]["new"], 1);
+    assert_eq!(data["overdue"]["by_deck"]["untagged"], 1);
+    let days = data["forecast"].as_array().unwrap();
+    assert_eq!(days.len(), 7);
+    assert_eq!(days[1]["due"], 1);
+    assert_eq!(days[6]["cumulative"], 2);
+    assert_eq!(data["beyond"], 0);
+
+    ringo_srs()
+        .args(["--data", path.to_str().unwrap(), "forecast", "--days", "0"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_activity() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_with_item(&dir);
+    let data = path.to_str().unwrap();
+    for _ in 0..2 {
+        ringo_srs()
+            .args(["--data", data, "review", "item_20260204_001", "correct"])
+            .assert()
+            .success();
+    }
+
+    let out = ringo_srs()
+        .args(["--data", data, "activity", "--goal", "3", "--heatmap"])
+        .assert()
+        .success()
+        .stderr(predicate::str::is_empty())
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    let data = &json["data"];
+    assert_eq!(data["current_streak"], 1);
+    assert_eq!(data["longest_streak"], 1);
+    assert_eq!(data["today"]["reviews"], 2);
+    assert_eq!(data["today"]["remaining"], 1);
+    assert_eq!(data["today"]["met"], false);
+    assert_eq!(data["total_reviews"], 2);
+    assert_eq!(data["heatmap"].as_array().unwrap().len(), 9);
+    assert!(data["heatmap"][1].as_str().unwrap().starts_with("Mon"));
+
+    let out = ringo_srs().args(["--data", path.to_str().unwrap(), "activity"]).output().unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert!(json["data"].get("heatmap").is_none());
+}
+
+#[test]
+fn test_analytics_retention() {
+    let dir = TempDir::new().unwrap();
+    let path = setup_with_item(&dir);
+    let data = path.to_str().unwrap();
+    for result in ["correct", "correct", "incorrect"] {
+        ringo_srs()
+            .args(["--data", data, "review", "item_20260204_001", result])
+            .assert()
+            .success();
+    }
+
+    let out = ringo_srs()
+        .args(["--data", data, "analytics", "retention"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    let data = &json["data"];
+    // The first review has nothing to predict from
+    assert_eq!(data["overall"]["reviews"], 2);
+    assert_eq!(data["overall"]["retention_pct"], 50.0);
+    assert_eq!(data["by_type"]["word"]["reviews"], 2);
+    assert_eq!(data["by_source"]["ringo-learning"]["correct"], 1);
+    // Reviewed immediately: predicted recall is near 100%
+    let calibration = data["calibration"].as_array().unwrap();
+    assert_eq!(calibration.len(), 10);
+    assert_eq!(calibration[9]["predicted_range"], "90-100%");
+    assert_eq!(calibration[9]["reviews"], 2);
+    assert!(data["brier"].as_f64().unwrap() > 0.4);
+}